@@ -6,12 +6,18 @@ async fn main() {
 
   let mut bundler = Dtsup::new(DtsupOptions {
     entry: "node-tests/_test/index.d.ts",
+    externals: vec![],
+    auto_externals: false,
+    inline_packages: vec![],
+    unresolved_import: Default::default(),
   });
 
   bundler.build().await.unwrap();
-  bundler.generate(DtsupGenerateOptions {
-    outdir: Some("abc"),
-  });
+  bundler
+    .generate(DtsupGenerateOptions {
+      outdir: Some("abc"),
+    })
+    .unwrap();
 
   // println!("\n\n Graph generated: {:?}", graph);
 }
@@ -0,0 +1,1211 @@
+use std::collections::{HashMap, HashSet};
+
+use swc_atoms::{js_word, JsWord};
+use swc_common::{Span, DUMMY_SP};
+use swc_ecma_ast::{
+  ArrowExpr, Class, ClassMember, ClassMethod, ClassProp, Decl, DefaultDecl, ExportDefaultExpr,
+  ExportSpecifier, Expr, Function, Ident, ImportSpecifier, Lit, MemberProp, MethodKind, ModuleDecl,
+  ModuleExportName, ModuleItem, ObjectPatProp, Param, ParamOrTsParamProp, Pat, PrivateName,
+  PrivateProp, PropName, Stmt, Str, TsEnumDecl, TsEnumMemberId, TsFnParam, TsFnType, TsKeywordType,
+  TsKeywordTypeKind, TsLit, TsLitType, TsModuleName, TsNamespaceBody, TsParamPropParam, TsType,
+  TsTypeAnn, UnaryOp, VarDecl, VarDeclKind, VarDeclarator,
+};
+
+use swc_ecma_visit::{Visit, VisitWith};
+
+use super::parse::SOURCE_MAP;
+use crate::result::{DtsupErrorType, Error};
+
+/// Generate declarations from TypeScript sources whose declarations are explicitly annotated,
+/// like `tsc --isolatedDeclarations`, so that sources are able to be bundled without `tsc`.
+/// Bodies and initializers are stripped, and declarations requiring inference are reported.
+pub fn generate_declarations(
+  file_name: &str,
+  mut module: swc_ecma_ast::Module,
+) -> Result<swc_ecma_ast::Module, Error> {
+  let mut name_collector = NameCollector::default();
+  module.visit_with(&mut name_collector);
+
+  let mut generator = DeclarationGenerator {
+    file_name,
+    diagnostics: Default::default(),
+    deferred: Default::default(),
+    used_names: name_collector.names,
+  };
+
+  module.body = generator.generate_module_items(std::mem::take(&mut module.body), false);
+  module.body = remove_unused_imports(std::mem::take(&mut module.body));
+
+  if generator.diagnostics.is_empty() {
+    Ok(module)
+  } else {
+    Err(Error::new_with_reason(
+      DtsupErrorType::DeclarationError,
+      &format!(
+        "[Declaration] unable to generate declarations without inference:\n{}",
+        generator.diagnostics.join("\n")
+      ),
+    ))
+  }
+}
+
+struct DeclarationGenerator<'a> {
+  file_name: &'a str,
+  diagnostics: Vec<String>,
+  /// Diagnostics of declarations which are not exported, keyed by their names,
+  /// they are only reported if the declarations are referenced by exports
+  deferred: Vec<(Vec<JsWord>, Vec<String>)>,
+  /// Names in the source, which are avoided by generated bindings
+  used_names: HashSet<JsWord>,
+}
+
+impl<'a> DeclarationGenerator<'a> {
+  fn report(&mut self, span: Span, message: &str) {
+    let loc = SOURCE_MAP.lookup_char_pos(span.lo);
+    self.diagnostics.push(format!(
+      "  {}:{}:{} {}",
+      self.file_name,
+      loc.line,
+      loc.col_display + 1,
+      message
+    ));
+  }
+
+  /// Diagnostics of declarations which are not exported are deferred,
+  /// see `report_reachable` for declarations which are referenced by exports.
+  fn generate_if_exported<T>(
+    &mut self,
+    names: Vec<JsWord>,
+    is_exported: bool,
+    generate: impl FnOnce(&mut Self) -> T,
+  ) -> T {
+    let len = self.diagnostics.len();
+    let generated = generate(self);
+
+    if !is_exported && self.diagnostics.len() > len {
+      let diagnostics = self.diagnostics.split_off(len);
+      self.deferred.push((names, diagnostics));
+    }
+
+    generated
+  }
+
+  /// Report deferred diagnostics of declarations which are referenced by exports, directly or through other
+  /// declarations, e.g. a class whose method is not annotated is referenced by an exported function.
+  /// Other declarations are dropped, they are not able to be generated, and nothing references them.
+  fn report_reachable(
+    &mut self,
+    items: Vec<ModuleItem>,
+    local_exports: &HashSet<JsWord>,
+  ) -> Vec<ModuleItem> {
+    let deferred = std::mem::take(&mut self.deferred);
+    if deferred.is_empty() {
+      return items;
+    }
+
+    let mut references: HashMap<JsWord, HashSet<JsWord>> = Default::default();
+    let mut reachable: Vec<JsWord> = Default::default();
+
+    items.iter().for_each(|item| {
+      let mut reference_collector = ReferenceCollector::default();
+      item.visit_with(&mut reference_collector);
+
+      match item {
+        ModuleItem::Stmt(Stmt::Decl(decl)) => {
+          let names = get_decl_names(decl);
+          if names.iter().any(|name| local_exports.contains(name)) {
+            reachable.extend(reference_collector.references);
+          } else {
+            names.into_iter().for_each(|name| {
+              references
+                .entry(name)
+                .or_default()
+                .extend(reference_collector.references.iter().cloned())
+            });
+          }
+        }
+        _ => reachable.extend(reference_collector.references),
+      }
+    });
+
+    let mut visited: HashSet<JsWord> = Default::default();
+    while let Some(name) = reachable.pop() {
+      if visited.insert(name.clone()) {
+        reachable.extend(references.get(&name).into_iter().flatten().cloned());
+      }
+    }
+
+    let mut unreachable: HashSet<JsWord> = Default::default();
+    deferred.into_iter().for_each(|(names, diagnostics)| {
+      if names.iter().any(|name| visited.contains(name)) {
+        self.diagnostics.extend(diagnostics);
+      } else {
+        unreachable.extend(names);
+      }
+    });
+
+    items
+      .into_iter()
+      .filter_map(|item| match item {
+        ModuleItem::Stmt(Stmt::Decl(Decl::Var(mut var_decl))) => {
+          var_decl.decls.retain(|declarator| {
+            !get_pat_names(&declarator.name)
+              .iter()
+              .any(|name| unreachable.contains(name))
+          });
+          (!var_decl.decls.is_empty()).then(|| ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))))
+        }
+        ModuleItem::Stmt(Stmt::Decl(decl))
+          if get_decl_names(&decl)
+            .iter()
+            .any(|name| unreachable.contains(name)) =>
+        {
+          None
+        }
+        item => Some(item),
+      })
+      .collect()
+  }
+
+  /// `is_ambient` is set in namespaces, where `declare` is not allowed
+  fn generate_module_items(&mut self, items: Vec<ModuleItem>, is_ambient: bool) -> Vec<ModuleItem> {
+    // namespaces are scopes of their own declarations
+    let outer_deferred = std::mem::take(&mut self.deferred);
+    let local_exports = collect_local_exports(&items);
+    let overloads = items
+      .iter()
+      .filter_map(|item| match item {
+        ModuleItem::Stmt(Stmt::Decl(Decl::Fn(fn_decl)))
+        | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(swc_ecma_ast::ExportDecl {
+          decl: Decl::Fn(fn_decl),
+          ..
+        }))
+          if fn_decl.function.body.is_none() =>
+        {
+          Some(fn_decl.ident.sym.clone())
+        }
+        _ => None,
+      })
+      .collect::<HashSet<_>>();
+
+    let items = items
+      .into_iter()
+      .flat_map(|item| -> Vec<ModuleItem> {
+        match item {
+          // side effects are not related to declarations
+          ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl))
+            if import_decl.specifiers.is_empty() =>
+          {
+            vec![]
+          }
+          ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(mut export_decl)) => {
+            match self.generate_decl(export_decl.decl, true, is_ambient, &overloads) {
+              Some(decl) => {
+                export_decl.decl = decl;
+                vec![ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl))]
+              }
+              None => vec![],
+            }
+          }
+          ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(mut export_default_decl)) => {
+            match &mut export_default_decl.decl {
+              DefaultDecl::Class(class_expr) => self.generate_class(&mut class_expr.class),
+              DefaultDecl::Fn(fn_expr) => self.generate_function(&mut fn_expr.function, true),
+              DefaultDecl::TsInterfaceDecl(_) => (),
+            }
+            vec![ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(
+              export_default_decl,
+            ))]
+          }
+          ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(export_default_expr)) => {
+            self.generate_default_expr(export_default_expr, is_ambient)
+          }
+          ModuleItem::ModuleDecl(module_decl) => vec![ModuleItem::ModuleDecl(module_decl)],
+          ModuleItem::Stmt(Stmt::Decl(decl)) => {
+            let is_exported = match &decl {
+              Decl::Class(class_decl) => local_exports.contains(&class_decl.ident.sym),
+              Decl::Fn(fn_decl) => local_exports.contains(&fn_decl.ident.sym),
+              Decl::TsEnum(ts_enum) => local_exports.contains(&ts_enum.id.sym),
+              Decl::TsModule(ts_module) => match &ts_module.id {
+                TsModuleName::Ident(ident) => local_exports.contains(&ident.sym),
+                TsModuleName::Str(_) => true,
+              },
+              // variables are checked by declarators
+              Decl::Var(_) | Decl::TsInterface(_) | Decl::TsTypeAlias(_) => true,
+            };
+
+            let decl = match decl {
+              Decl::Var(var_decl) => self.generate_var_decl(
+                var_decl,
+                |name| matches!(name, Pat::Ident(binding_ident) if local_exports.contains(&binding_ident.id.sym)),
+                is_ambient,
+              ),
+              decl => self.generate_decl(decl, is_exported, is_ambient, &overloads),
+            };
+
+            decl
+              .map(|decl| ModuleItem::Stmt(Stmt::Decl(decl)))
+              .into_iter()
+              .collect()
+          }
+          // implementation details
+          ModuleItem::Stmt(_) => vec![],
+        }
+      })
+      .collect();
+
+    let items = self.report_reachable(items, &local_exports);
+    self.deferred = outer_deferred;
+    items
+  }
+
+  fn generate_decl(
+    &mut self,
+    decl: Decl,
+    is_exported: bool,
+    is_ambient: bool,
+    overloads: &HashSet<JsWord>,
+  ) -> Option<Decl> {
+    match decl {
+      Decl::Fn(mut fn_decl) => {
+        if fn_decl.declare {
+          return Some(Decl::Fn(fn_decl));
+        }
+        // implementation signatures of overloads are not visible
+        if fn_decl.function.body.is_some() && overloads.contains(&fn_decl.ident.sym) {
+          return None;
+        }
+
+        let names = vec![fn_decl.ident.sym.clone()];
+        Some(self.generate_if_exported(names, is_exported, |generator| {
+          generator.generate_function(&mut fn_decl.function, true);
+          fn_decl.declare = !is_ambient;
+          Decl::Fn(fn_decl)
+        }))
+      }
+      Decl::Class(mut class_decl) => {
+        if class_decl.declare {
+          return Some(Decl::Class(class_decl));
+        }
+
+        let names = vec![class_decl.ident.sym.clone()];
+        Some(self.generate_if_exported(names, is_exported, |generator| {
+          generator.generate_class(&mut class_decl.class);
+          class_decl.declare = !is_ambient;
+          Decl::Class(class_decl)
+        }))
+      }
+      Decl::Var(var_decl) => self.generate_var_decl(var_decl, |_| is_exported, is_ambient),
+      Decl::TsEnum(mut ts_enum) => {
+        if ts_enum.declare {
+          return Some(Decl::TsEnum(ts_enum));
+        }
+
+        let names = vec![ts_enum.id.sym.clone()];
+        Some(self.generate_if_exported(names, is_exported, |generator| {
+          generator.generate_enum(&ts_enum);
+          ts_enum.declare = !is_ambient;
+          Decl::TsEnum(ts_enum)
+        }))
+      }
+      Decl::TsModule(mut ts_module) => {
+        if ts_module.declare || ts_module.global || matches!(ts_module.id, TsModuleName::Str(_)) {
+          return Some(Decl::TsModule(ts_module));
+        }
+
+        if let Some(body) = ts_module.body.as_mut() {
+          self.generate_namespace_body(body);
+        }
+        ts_module.declare = !is_ambient;
+        Some(Decl::TsModule(ts_module))
+      }
+      Decl::TsInterface(_) | Decl::TsTypeAlias(_) => Some(decl),
+    }
+  }
+
+  /// Initializers are kept in declarations, which are required to be constant,
+  /// e.g. `A = 1`, `B = "b"` or `C = A | 2`, but not `D = "d".length`
+  fn generate_enum(&mut self, ts_enum: &TsEnumDecl) {
+    let mut members: HashSet<JsWord> = Default::default();
+
+    ts_enum.members.iter().for_each(|member| {
+      let name = match &member.id {
+        TsEnumMemberId::Ident(ident) => ident.sym.clone(),
+        TsEnumMemberId::Str(s) => s.value.clone(),
+      };
+
+      if let Some(init) = &member.init {
+        if !is_constant_enum_expr(init, &ts_enum.id.sym, &members) {
+          self.report(
+            member.span,
+            &format!("enum member `{}` requires a constant initializer", name),
+          );
+        }
+      }
+
+      members.insert(name);
+    });
+  }
+
+  fn generate_namespace_body(&mut self, body: &mut TsNamespaceBody) {
+    match body {
+      TsNamespaceBody::TsModuleBlock(block) => {
+        block.body = self.generate_module_items(std::mem::take(&mut block.body), true);
+      }
+      TsNamespaceBody::TsNamespaceDecl(namespace_decl) => {
+        self.generate_namespace_body(&mut namespace_decl.body)
+      }
+    }
+  }
+
+  fn generate_var_decl(
+    &mut self,
+    mut var_decl: VarDecl,
+    is_exported: impl Fn(&Pat) -> bool,
+    is_ambient: bool,
+  ) -> Option<Decl> {
+    if var_decl.declare {
+      return Some(Decl::Var(var_decl));
+    }
+
+    let kind = var_decl.kind;
+    var_decl.decls = std::mem::take(&mut var_decl.decls)
+      .into_iter()
+      .map(|declarator| {
+        let names = get_pat_names(&declarator.name);
+        self.generate_if_exported(names, is_exported(&declarator.name), |generator| {
+          generator.generate_var_declarator(declarator, kind)
+        })
+      })
+      .collect();
+
+    var_decl.declare = !is_ambient;
+    Some(Decl::Var(var_decl))
+  }
+
+  fn generate_var_declarator(
+    &mut self,
+    mut declarator: VarDeclarator,
+    kind: VarDeclKind,
+  ) -> VarDeclarator {
+    let init = declarator.init.take();
+    declarator.definite = false;
+
+    match &mut declarator.name {
+      Pat::Ident(binding_ident) => {
+        if binding_ident.type_ann.is_none() {
+          binding_ident.type_ann = init
+            .and_then(|init| self.infer_expr_type(&init, kind != VarDeclKind::Const))
+            .map(type_ann);
+        }
+
+        if binding_ident.type_ann.is_none() {
+          self.report(
+            declarator.span,
+            &format!(
+              "variable `{}` requires an explicit type annotation",
+              binding_ident.id.sym
+            ),
+          );
+        }
+      }
+      _ => self.report(
+        declarator.span,
+        "destructuring declarations require explicit declarations of each variable",
+      ),
+    }
+
+    declarator
+  }
+
+  /// `export default expr` is generated as `declare const _default: T; export default _default;`,
+  /// `_default` is renamed to `_default$1` if it is used in the source
+  fn generate_default_expr(
+    &mut self,
+    export_default_expr: ExportDefaultExpr,
+    is_ambient: bool,
+  ) -> Vec<ModuleItem> {
+    if let Expr::Ident(_) = export_default_expr.expr.as_ref() {
+      return vec![ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(
+        export_default_expr,
+      ))];
+    }
+
+    let ts_type = match self.infer_expr_type(&export_default_expr.expr, true) {
+      Some(ts_type) => ts_type,
+      None => {
+        self.report(
+          export_default_expr.span,
+          "default export requires an explicit type annotation, e.g. `expr as T`",
+        );
+        return vec![];
+      }
+    };
+
+    let mut name = JsWord::from("_default");
+    let mut count = 1;
+    while self.used_names.contains(&name) {
+      name = format!("_default${}", count).into();
+      count += 1;
+    }
+    self.used_names.insert(name.clone());
+
+    let ident = Ident::new(name, DUMMY_SP);
+    vec![
+      ModuleItem::Stmt(Stmt::Decl(Decl::Var(VarDecl {
+        span: DUMMY_SP,
+        kind: VarDeclKind::Const,
+        declare: !is_ambient,
+        decls: vec![VarDeclarator {
+          span: DUMMY_SP,
+          name: Pat::Ident(swc_ecma_ast::BindingIdent {
+            id: ident.clone(),
+            type_ann: Some(type_ann(ts_type)),
+          }),
+          init: None,
+          definite: false,
+        }],
+      }))),
+      ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(ExportDefaultExpr {
+        span: export_default_expr.span,
+        expr: Box::new(Expr::Ident(ident)),
+      })),
+    ]
+  }
+
+  fn generate_function(&mut self, function: &mut Function, requires_return_type: bool) {
+    function.body = None;
+    function.is_async = false;
+    function.is_generator = false;
+    function.decorators.clear();
+
+    function.params.iter_mut().for_each(|param| {
+      param.decorators.clear();
+      param.pat = self.generate_param(std::mem::replace(&mut param.pat, Pat::Invalid(invalid())));
+    });
+
+    if requires_return_type && function.return_type.is_none() {
+      self.report(function.span, "function requires an explicit return type");
+    }
+  }
+
+  /// Defaults are stripped from parameters, parameters with defaults are optional
+  fn generate_param(&mut self, pat: Pat) -> Pat {
+    match pat {
+      Pat::Assign(assign_pat) => {
+        let ts_type = get_pat_type_ann(&assign_pat.left)
+          .cloned()
+          .or(assign_pat.type_ann)
+          .or_else(|| self.infer_expr_type(&assign_pat.right, true).map(type_ann));
+
+        let mut pat = strip_pat_defaults(*assign_pat.left);
+        match &mut pat {
+          Pat::Ident(binding_ident) => {
+            binding_ident.id.optional = true;
+            binding_ident.type_ann = ts_type;
+          }
+          Pat::Array(array_pat) => {
+            array_pat.optional = true;
+            array_pat.type_ann = ts_type;
+          }
+          Pat::Object(object_pat) => {
+            object_pat.optional = true;
+            object_pat.type_ann = ts_type;
+          }
+          _ => (),
+        }
+
+        if get_pat_type_ann(&pat).is_none() {
+          self.report(
+            assign_pat.span,
+            "parameter requires an explicit type annotation",
+          );
+        }
+        pat
+      }
+      Pat::Rest(rest_pat) => {
+        if rest_pat.type_ann.is_none() && get_pat_type_ann(&rest_pat.arg).is_none() {
+          self.report(
+            rest_pat.span,
+            "parameter requires an explicit type annotation",
+          );
+        }
+        strip_pat_defaults(Pat::Rest(rest_pat))
+      }
+      pat => {
+        if get_pat_type_ann(&pat).is_none() {
+          self.report(
+            get_pat_span(&pat),
+            "parameter requires an explicit type annotation",
+          );
+        }
+        strip_pat_defaults(pat)
+      }
+    }
+  }
+
+  fn generate_class(&mut self, class: &mut Class) {
+    class.decorators.clear();
+
+    if let Some(super_class) = &class.super_class {
+      if !is_entity_name_expr(super_class) {
+        self.report(
+          class.span,
+          "class extending an expression requires the expression to be declared as a variable",
+        );
+      }
+    }
+
+    let key_of = |key: &PropName| match key {
+      PropName::Ident(ident) => Some(ident.sym.clone()),
+      PropName::Str(s) => Some(s.value.clone()),
+      _ => None,
+    };
+
+    let overloads = class
+      .body
+      .iter()
+      .filter_map(|member| match member {
+        ClassMember::Method(method) if method.function.body.is_none() && !method.is_abstract => {
+          key_of(&method.key)
+        }
+        ClassMember::Constructor(constructor) if constructor.body.is_none() => {
+          Some(js_word!("constructor"))
+        }
+        _ => None,
+      })
+      .collect::<HashSet<_>>();
+
+    let setter_types = class
+      .body
+      .iter()
+      .filter_map(|member| match member {
+        ClassMember::Method(method) if method.kind == MethodKind::Setter => Some((
+          key_of(&method.key)?,
+          method
+            .function
+            .params
+            .first()
+            .and_then(|param| get_pat_type_ann(&param.pat))?
+            .clone(),
+        )),
+        _ => None,
+      })
+      .collect::<Vec<_>>();
+
+    let mut has_private_names = false;
+    let mut param_props: Vec<ClassMember> = Default::default();
+
+    class.body = std::mem::take(&mut class.body)
+      .into_iter()
+      .filter_map(|member| match member {
+        ClassMember::Constructor(mut constructor) => {
+          if constructor.body.is_some() && overloads.contains(&js_word!("constructor")) {
+            return None;
+          }
+          constructor.body = None;
+
+          // parameter properties are only allowed in implementations
+          constructor.params = std::mem::take(&mut constructor.params)
+            .into_iter()
+            .map(|param| {
+              let (pat, param_prop) = match param {
+                ParamOrTsParamProp::Param(param) => (param.pat, None),
+                ParamOrTsParamProp::TsParamProp(ts_param_prop) => {
+                  let pat = match &ts_param_prop.param {
+                    TsParamPropParam::Ident(binding_ident) => Pat::Ident(binding_ident.clone()),
+                    TsParamPropParam::Assign(assign_pat) => Pat::Assign(assign_pat.clone()),
+                  };
+                  (pat, Some(ts_param_prop))
+                }
+              };
+
+              let pat = self.generate_param(pat);
+              if let (Some(param_prop), Pat::Ident(binding_ident)) = (param_prop, &pat) {
+                let is_private =
+                  param_prop.accessibility == Some(swc_ecma_ast::Accessibility::Private);
+                param_props.push(ClassMember::ClassProp(ClassProp {
+                  span: param_prop.span,
+                  key: PropName::Ident(Ident::new(
+                    binding_ident.id.sym.clone(),
+                    binding_ident.id.span,
+                  )),
+                  value: None,
+                  type_ann: (!is_private)
+                    .then(|| binding_ident.type_ann.clone())
+                    .flatten(),
+                  is_static: false,
+                  decorators: vec![],
+                  accessibility: param_prop.accessibility,
+                  is_abstract: false,
+                  is_optional: binding_ident.id.optional,
+                  is_override: param_prop.is_override,
+                  readonly: param_prop.readonly,
+                  declare: false,
+                  definite: false,
+                }));
+              }
+
+              ParamOrTsParamProp::Param(Param {
+                span: DUMMY_SP,
+                decorators: vec![],
+                pat,
+              })
+            })
+            .collect();
+
+          Some(ClassMember::Constructor(constructor))
+        }
+        ClassMember::Method(method)
+          if method.accessibility == Some(swc_ecma_ast::Accessibility::Private) =>
+        {
+          // only the implementation is kept as a property without types
+          method.function.body.as_ref()?;
+          Some(private_prop(method))
+        }
+        ClassMember::Method(mut method) => {
+          if method.function.body.is_some()
+            && key_of(&method.key).is_some_and(|key| overloads.contains(&key))
+          {
+            return None;
+          }
+
+          if method.kind == MethodKind::Getter && method.function.return_type.is_none() {
+            method.function.return_type = key_of(&method.key).and_then(|key| {
+              setter_types
+                .iter()
+                .find(|(setter_key, _)| *setter_key == key)
+                .map(|(_, ts_type)| ts_type.clone())
+            });
+          }
+
+          self.generate_function(&mut method.function, method.kind != MethodKind::Setter);
+          Some(ClassMember::Method(method))
+        }
+        ClassMember::ClassProp(mut class_prop) => {
+          let value = class_prop.value.take();
+          class_prop.decorators.clear();
+          class_prop.declare = false;
+          class_prop.definite = false;
+
+          if class_prop.accessibility == Some(swc_ecma_ast::Accessibility::Private) {
+            class_prop.type_ann = None;
+          } else if class_prop.type_ann.is_none() {
+            if let Some(value) = value {
+              class_prop.type_ann = self
+                .infer_expr_type(&value, !class_prop.readonly)
+                .map(type_ann);
+
+              if class_prop.type_ann.is_none() {
+                self.report(
+                  class_prop.span,
+                  "property requires an explicit type annotation",
+                );
+              }
+            }
+          }
+
+          Some(ClassMember::ClassProp(class_prop))
+        }
+        ClassMember::PrivateMethod(_) | ClassMember::PrivateProp(_) => {
+          has_private_names = true;
+          None
+        }
+        ClassMember::TsIndexSignature(_) => Some(member),
+        ClassMember::Empty(_) | ClassMember::StaticBlock(_) => None,
+      })
+      .collect();
+
+    // `#private` keeps the class nominal, as `tsc` does
+    if has_private_names {
+      param_props.insert(
+        0,
+        ClassMember::PrivateProp(PrivateProp {
+          span: DUMMY_SP,
+          key: PrivateName {
+            span: DUMMY_SP,
+            id: Ident::new("private".into(), DUMMY_SP),
+          },
+          value: None,
+          type_ann: None,
+          is_static: false,
+          decorators: vec![],
+          accessibility: None,
+          is_optional: false,
+          is_override: false,
+          readonly: false,
+          definite: false,
+        }),
+      );
+    }
+
+    param_props.append(&mut class.body);
+    class.body = param_props;
+  }
+
+  /// Types of expressions which are able to be known without inference,
+  /// literals are widened for mutable bindings, e.g. `number` for `let a = 1`
+  fn infer_expr_type(&mut self, expr: &Expr, widen: bool) -> Option<Box<TsType>> {
+    let keyword = |kind: TsKeywordTypeKind| {
+      Some(Box::new(TsType::TsKeywordType(TsKeywordType {
+        span: DUMMY_SP,
+        kind,
+      })))
+    };
+    let literal = |lit: TsLit| {
+      Some(Box::new(TsType::TsLitType(TsLitType {
+        span: DUMMY_SP,
+        lit,
+      })))
+    };
+
+    match expr {
+      Expr::Lit(Lit::Str(_)) if widen => keyword(TsKeywordTypeKind::TsStringKeyword),
+      Expr::Lit(Lit::Str(s)) => literal(TsLit::Str(s.clone())),
+      Expr::Lit(Lit::Num(_)) if widen => keyword(TsKeywordTypeKind::TsNumberKeyword),
+      Expr::Lit(Lit::Num(n)) => literal(TsLit::Number(*n)),
+      Expr::Lit(Lit::Bool(_)) if widen => keyword(TsKeywordTypeKind::TsBooleanKeyword),
+      Expr::Lit(Lit::Bool(b)) => literal(TsLit::Bool(*b)),
+      Expr::Lit(Lit::BigInt(_)) if widen => keyword(TsKeywordTypeKind::TsBigIntKeyword),
+      Expr::Lit(Lit::BigInt(b)) => literal(TsLit::BigInt(b.clone())),
+      Expr::Tpl(tpl) if tpl.exprs.is_empty() => {
+        // `const a = \`foo\`` is `"foo"`, templates with invalid escapes are not cooked
+        match tpl.quasis.first().and_then(|quasi| quasi.cooked.as_ref()) {
+          Some(cooked) if !widen => literal(TsLit::Str(Str {
+            span: DUMMY_SP,
+            value: cooked.clone(),
+            raw: None,
+          })),
+          _ => keyword(TsKeywordTypeKind::TsStringKeyword),
+        }
+      }
+      Expr::Unary(unary) if unary.op == UnaryOp::Minus => match unary.arg.as_ref() {
+        Expr::Lit(Lit::Num(_)) if widen => keyword(TsKeywordTypeKind::TsNumberKeyword),
+        Expr::Lit(Lit::Num(n)) => literal(TsLit::Number(swc_ecma_ast::Number {
+          span: n.span,
+          value: -n.value,
+        })),
+        Expr::Lit(Lit::BigInt(_)) if widen => keyword(TsKeywordTypeKind::TsBigIntKeyword),
+        Expr::Lit(Lit::BigInt(b)) => literal(TsLit::BigInt(swc_ecma_ast::BigInt {
+          span: b.span,
+          value: -b.value.clone(),
+        })),
+        _ => None,
+      },
+      Expr::TsAs(ts_as) => Some(ts_as.type_ann.clone()),
+      Expr::TsTypeAssertion(ts_type_assertion) => Some(ts_type_assertion.type_ann.clone()),
+      Expr::Paren(paren) => self.infer_expr_type(&paren.expr, widen),
+      Expr::Arrow(arrow) => Some(self.generate_fn_type(arrow_to_function(arrow))),
+      Expr::Fn(fn_expr) => Some(self.generate_fn_type(fn_expr.function.clone())),
+      _ => None,
+    }
+  }
+
+  fn generate_fn_type(&mut self, mut function: Function) -> Box<TsType> {
+    self.generate_function(&mut function, true);
+
+    let params = function
+      .params
+      .into_iter()
+      .filter_map(|param| match param.pat {
+        Pat::Ident(binding_ident) => Some(TsFnParam::Ident(binding_ident)),
+        Pat::Array(array_pat) => Some(TsFnParam::Array(array_pat)),
+        Pat::Rest(rest_pat) => Some(TsFnParam::Rest(rest_pat)),
+        Pat::Object(object_pat) => Some(TsFnParam::Object(object_pat)),
+        _ => None,
+      })
+      .collect();
+
+    Box::new(TsType::from(TsFnType {
+      span: DUMMY_SP,
+      params,
+      type_params: function.type_params,
+      type_ann: function.return_type.unwrap_or_else(|| {
+        type_ann(Box::new(TsType::TsKeywordType(TsKeywordType {
+          span: DUMMY_SP,
+          kind: TsKeywordTypeKind::TsAnyKeyword,
+        })))
+      }),
+    }))
+  }
+}
+
+/// Imports which are only used by implementations are removed, e.g. `import { readFileSync } from "fs"`
+fn remove_unused_imports(items: Vec<ModuleItem>) -> Vec<ModuleItem> {
+  let mut reference_collector = ReferenceCollector::default();
+  items
+    .iter()
+    .filter(|item| {
+      !matches!(
+        item,
+        ModuleItem::ModuleDecl(ModuleDecl::Import(_) | ModuleDecl::TsImportEquals(_))
+      )
+    })
+    .for_each(|item| item.visit_with(&mut reference_collector));
+  let references = reference_collector.references;
+
+  items
+    .into_iter()
+    .filter_map(|item| match item {
+      ModuleItem::ModuleDecl(ModuleDecl::Import(mut import_decl)) => {
+        import_decl.specifiers.retain(|specifier| {
+          let local = match specifier {
+            ImportSpecifier::Named(named) => &named.local,
+            ImportSpecifier::Default(default) => &default.local,
+            ImportSpecifier::Namespace(namespace) => &namespace.local,
+          };
+          references.contains(&local.sym)
+        });
+        (!import_decl.specifiers.is_empty())
+          .then(|| ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)))
+      }
+      ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(import_equals))
+        if !import_equals.is_export && !references.contains(&import_equals.id.sym) =>
+      {
+        None
+      }
+      item => Some(item),
+    })
+    .collect()
+}
+
+/// Local names exported by `export { foo }`, `export default foo` and `export = foo`
+fn collect_local_exports(items: &[ModuleItem]) -> HashSet<JsWord> {
+  items
+    .iter()
+    .flat_map(|item| match item {
+      ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export_named))
+        if export_named.src.is_none() =>
+      {
+        export_named
+          .specifiers
+          .iter()
+          .filter_map(|specifier| match specifier {
+            ExportSpecifier::Named(named) => match &named.orig {
+              ModuleExportName::Ident(ident) => Some(ident.sym.clone()),
+              ModuleExportName::Str(_) => None,
+            },
+            _ => None,
+          })
+          .collect()
+      }
+      ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultExpr(ExportDefaultExpr { expr, .. }))
+      | ModuleItem::ModuleDecl(ModuleDecl::TsExportAssignment(
+        swc_ecma_ast::TsExportAssignment { expr, .. },
+      )) => match expr.as_ref() {
+        Expr::Ident(ident) => vec![ident.sym.clone()],
+        _ => vec![],
+      },
+      _ => vec![],
+    })
+    .collect()
+}
+
+/// Names declared by a declaration, e.g. `a` and `b` in `const { a, b }: Props`
+fn get_decl_names(decl: &Decl) -> Vec<JsWord> {
+  match decl {
+    Decl::Class(class_decl) => vec![class_decl.ident.sym.clone()],
+    Decl::Fn(fn_decl) => vec![fn_decl.ident.sym.clone()],
+    Decl::Var(var_decl) => var_decl
+      .decls
+      .iter()
+      .flat_map(|declarator| get_pat_names(&declarator.name))
+      .collect(),
+    Decl::TsInterface(ts_interface) => vec![ts_interface.id.sym.clone()],
+    Decl::TsTypeAlias(ts_type_alias) => vec![ts_type_alias.id.sym.clone()],
+    Decl::TsEnum(ts_enum) => vec![ts_enum.id.sym.clone()],
+    Decl::TsModule(ts_module) => match &ts_module.id {
+      TsModuleName::Ident(ident) => vec![ident.sym.clone()],
+      TsModuleName::Str(_) => vec![],
+    },
+  }
+}
+
+fn get_pat_names(pat: &Pat) -> Vec<JsWord> {
+  match pat {
+    Pat::Ident(binding_ident) => vec![binding_ident.id.sym.clone()],
+    Pat::Array(array_pat) => array_pat
+      .elems
+      .iter()
+      .flatten()
+      .flat_map(get_pat_names)
+      .collect(),
+    Pat::Object(object_pat) => object_pat
+      .props
+      .iter()
+      .flat_map(|prop| match prop {
+        ObjectPatProp::KeyValue(key_value) => get_pat_names(&key_value.value),
+        ObjectPatProp::Assign(assign) => vec![assign.key.sym.clone()],
+        ObjectPatProp::Rest(rest) => get_pat_names(&rest.arg),
+      })
+      .collect(),
+    Pat::Rest(rest_pat) => get_pat_names(&rest_pat.arg),
+    Pat::Assign(assign_pat) => get_pat_names(&assign_pat.left),
+    Pat::Invalid(_) | Pat::Expr(_) => vec![],
+  }
+}
+
+/// Collects all names in the source
+#[derive(Default)]
+struct NameCollector {
+  names: HashSet<JsWord>,
+}
+
+impl Visit for NameCollector {
+  fn visit_ident(&mut self, n: &Ident) {
+    self.names.insert(n.sym.clone());
+  }
+}
+
+/// Collects names referenced by declarations, names of declarations, properties and members are not references
+#[derive(Default)]
+struct ReferenceCollector {
+  references: HashSet<JsWord>,
+}
+
+impl Visit for ReferenceCollector {
+  fn visit_ident(&mut self, n: &Ident) {
+    self.references.insert(n.sym.clone());
+  }
+
+  fn visit_binding_ident(&mut self, n: &swc_ecma_ast::BindingIdent) {
+    n.type_ann.visit_with(self);
+  }
+
+  fn visit_prop_name(&mut self, n: &PropName) {
+    if let PropName::Computed(computed) = n {
+      computed.visit_with(self);
+    }
+  }
+
+  fn visit_member_prop(&mut self, n: &swc_ecma_ast::MemberProp) {
+    if let swc_ecma_ast::MemberProp::Computed(computed) = n {
+      computed.visit_with(self);
+    }
+  }
+
+  fn visit_ts_qualified_name(&mut self, n: &swc_ecma_ast::TsQualifiedName) {
+    n.left.visit_with(self);
+  }
+
+  fn visit_ts_import_type(&mut self, n: &swc_ecma_ast::TsImportType) {
+    n.type_args.visit_with(self);
+  }
+
+  fn visit_ts_property_signature(&mut self, n: &swc_ecma_ast::TsPropertySignature) {
+    if n.computed {
+      n.key.visit_with(self);
+    }
+    n.params.visit_with(self);
+    n.type_ann.visit_with(self);
+    n.type_params.visit_with(self);
+  }
+
+  fn visit_ts_method_signature(&mut self, n: &swc_ecma_ast::TsMethodSignature) {
+    if n.computed {
+      n.key.visit_with(self);
+    }
+    n.params.visit_with(self);
+    n.type_ann.visit_with(self);
+    n.type_params.visit_with(self);
+  }
+
+  fn visit_ts_getter_signature(&mut self, n: &swc_ecma_ast::TsGetterSignature) {
+    if n.computed {
+      n.key.visit_with(self);
+    }
+    n.type_ann.visit_with(self);
+  }
+
+  fn visit_ts_setter_signature(&mut self, n: &swc_ecma_ast::TsSetterSignature) {
+    if n.computed {
+      n.key.visit_with(self);
+    }
+    n.param.visit_with(self);
+  }
+
+  fn visit_ts_enum_member(&mut self, n: &swc_ecma_ast::TsEnumMember) {
+    n.init.visit_with(self);
+  }
+
+  fn visit_ts_type_param(&mut self, n: &swc_ecma_ast::TsTypeParam) {
+    n.constraint.visit_with(self);
+    n.default.visit_with(self);
+  }
+
+  fn visit_fn_decl(&mut self, n: &swc_ecma_ast::FnDecl) {
+    n.function.visit_with(self);
+  }
+
+  fn visit_class_decl(&mut self, n: &swc_ecma_ast::ClassDecl) {
+    n.class.visit_with(self);
+  }
+
+  fn visit_ts_interface_decl(&mut self, n: &swc_ecma_ast::TsInterfaceDecl) {
+    n.type_params.visit_with(self);
+    n.extends.visit_with(self);
+    n.body.visit_with(self);
+  }
+
+  fn visit_ts_type_alias_decl(&mut self, n: &swc_ecma_ast::TsTypeAliasDecl) {
+    n.type_params.visit_with(self);
+    n.type_ann.visit_with(self);
+  }
+
+  fn visit_ts_enum_decl(&mut self, n: &swc_ecma_ast::TsEnumDecl) {
+    n.members.visit_with(self);
+  }
+
+  fn visit_ts_module_decl(&mut self, n: &swc_ecma_ast::TsModuleDecl) {
+    n.body.visit_with(self);
+  }
+
+  fn visit_export_named_specifier(&mut self, n: &swc_ecma_ast::ExportNamedSpecifier) {
+    n.orig.visit_with(self);
+  }
+}
+
+/// Constant enum expressions are literals and operations on them, which may refer to previous members of the enum,
+/// e.g. `A`, `Enum.A` or `Enum["A"]`.
+/// See: https://www.typescriptlang.org/docs/handbook/enums.html#computed-and-constant-members
+fn is_constant_enum_expr(expr: &Expr, enum_name: &JsWord, members: &HashSet<JsWord>) -> bool {
+  match expr {
+    Expr::Lit(Lit::Num(_) | Lit::Str(_)) => true,
+    Expr::Tpl(tpl) => tpl
+      .exprs
+      .iter()
+      .all(|expr| is_constant_enum_expr(expr, enum_name, members)),
+    Expr::Paren(paren) => is_constant_enum_expr(&paren.expr, enum_name, members),
+    Expr::Unary(unary) => {
+      matches!(unary.op, UnaryOp::Minus | UnaryOp::Plus | UnaryOp::Tilde)
+        && is_constant_enum_expr(&unary.arg, enum_name, members)
+    }
+    Expr::Bin(bin) => {
+      is_constant_enum_expr(&bin.left, enum_name, members)
+        && is_constant_enum_expr(&bin.right, enum_name, members)
+    }
+    Expr::Ident(ident) => members.contains(&ident.sym),
+    Expr::Member(member) => {
+      let is_enum = matches!(member.obj.as_ref(), Expr::Ident(obj) if obj.sym == *enum_name);
+      is_enum
+        && match &member.prop {
+          MemberProp::Ident(ident) => members.contains(&ident.sym),
+          MemberProp::Computed(computed) => {
+            matches!(computed.expr.as_ref(), Expr::Lit(Lit::Str(s)) if members.contains(&s.value))
+          }
+          MemberProp::PrivateName(_) => false,
+        }
+    }
+    _ => false,
+  }
+}
+
+/// Default values are not allowed in declarations, e.g. `{ a = 1 }: Props` -> `{ a }: Props`
+fn strip_pat_defaults(pat: Pat) -> Pat {
+  match pat {
+    Pat::Assign(assign_pat) => strip_pat_defaults(*assign_pat.left),
+    Pat::Array(mut array_pat) => {
+      array_pat.elems = array_pat
+        .elems
+        .into_iter()
+        .map(|elem| elem.map(strip_pat_defaults))
+        .collect();
+      Pat::Array(array_pat)
+    }
+    Pat::Object(mut object_pat) => {
+      object_pat.props.iter_mut().for_each(|prop| match prop {
+        ObjectPatProp::KeyValue(key_value) => {
+          let value = std::mem::replace(&mut *key_value.value, Pat::Invalid(invalid()));
+          *key_value.value = strip_pat_defaults(value);
+        }
+        ObjectPatProp::Assign(assign) => assign.value = None,
+        ObjectPatProp::Rest(_) => (),
+      });
+      Pat::Object(object_pat)
+    }
+    Pat::Rest(mut rest_pat) => {
+      let arg = std::mem::replace(&mut *rest_pat.arg, Pat::Invalid(invalid()));
+      *rest_pat.arg = strip_pat_defaults(arg);
+      Pat::Rest(rest_pat)
+    }
+    pat => pat,
+  }
+}
+
+fn get_pat_type_ann(pat: &Pat) -> Option<&TsTypeAnn> {
+  match pat {
+    Pat::Ident(binding_ident) => binding_ident.type_ann.as_ref(),
+    Pat::Array(array_pat) => array_pat.type_ann.as_ref(),
+    Pat::Object(object_pat) => object_pat.type_ann.as_ref(),
+    Pat::Rest(rest_pat) => rest_pat.type_ann.as_ref(),
+    Pat::Assign(assign_pat) => assign_pat.type_ann.as_ref(),
+    Pat::Invalid(_) | Pat::Expr(_) => None,
+  }
+}
+
+fn get_pat_span(pat: &Pat) -> Span {
+  match pat {
+    Pat::Ident(binding_ident) => binding_ident.id.span,
+    Pat::Array(array_pat) => array_pat.span,
+    Pat::Object(object_pat) => object_pat.span,
+    Pat::Rest(rest_pat) => rest_pat.span,
+    Pat::Assign(assign_pat) => assign_pat.span,
+    Pat::Invalid(invalid) => invalid.span,
+    Pat::Expr(_) => DUMMY_SP,
+  }
+}
+
+/// Entity names like `Foo` or `foo.Bar` are able to be referenced in `extends` of declarations
+fn is_entity_name_expr(expr: &Expr) -> bool {
+  match expr {
+    Expr::Ident(_) => true,
+    Expr::Member(member) => {
+      matches!(member.prop, swc_ecma_ast::MemberProp::Ident(_)) && is_entity_name_expr(&member.obj)
+    }
+    _ => false,
+  }
+}
+
+fn private_prop(method: ClassMethod) -> ClassMember {
+  ClassMember::ClassProp(ClassProp {
+    span: method.span,
+    key: method.key,
+    value: None,
+    type_ann: None,
+    is_static: method.is_static,
+    decorators: vec![],
+    accessibility: method.accessibility,
+    is_abstract: false,
+    is_optional: method.is_optional,
+    is_override: method.is_override,
+    readonly: false,
+    declare: false,
+    definite: false,
+  })
+}
+
+fn arrow_to_function(arrow: &ArrowExpr) -> Function {
+  Function {
+    params: arrow.params.iter().cloned().map(Param::from).collect(),
+    decorators: vec![],
+    span: arrow.span,
+    body: None,
+    is_generator: arrow.is_generator,
+    is_async: arrow.is_async,
+    type_params: arrow.type_params.clone(),
+    return_type: arrow.return_type.clone(),
+  }
+}
+
+#[inline]
+fn type_ann(ts_type: Box<TsType>) -> TsTypeAnn {
+  TsTypeAnn {
+    span: DUMMY_SP,
+    type_ann: ts_type,
+  }
+}
+
+#[inline]
+fn invalid() -> swc_ecma_ast::Invalid {
+  swc_ecma_ast::Invalid { span: DUMMY_SP }
+}
@@ -1,3 +1,4 @@
+pub(crate) mod declaration;
 pub(crate) mod module;
 pub(crate) mod module_analyzer;
 pub(crate) mod parse;
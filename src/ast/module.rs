@@ -1,28 +1,32 @@
 use std::collections::{HashMap, HashSet};
 
-use rayon::prelude::*;
 use smol_str::SmolStr;
 use swc_atoms::JsWord;
 use swc_common::Mark;
-use swc_ecma_ast::{ImportSpecifier, ModuleDecl, ModuleItem, TsModuleRef};
-use swc_ecma_visit::VisitMutWith;
+use swc_ecma_ast::{ModuleDecl, ModuleItem, TsImportType, TsModuleRef};
+use swc_ecma_visit::{Visit, VisitMutWith, VisitWith};
 
 use super::{
   module_analyzer::{
     ModuleAnalyzer, ModuleExport, ModuleExportName, ModuleExportNamespace, ModuleImport,
     StatementContext,
   },
+  parse::SOURCE_MAP,
+  scope::Meaning,
   statement::Statement,
   symbol,
+  utils::split_var_decls,
 };
-use crate::utils::resolve_id;
+use crate::externals::Externals;
+use crate::result::{DtsupErrorType, Error};
+use crate::utils::{resolve_id, resolve_package};
 
 pub type ModuleId = SmolStr;
 
 pub type LocalName = JsWord;
 pub type Source = JsWord;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum ImportIdent {
   Name(JsWord),
   Namespace,
@@ -53,6 +57,21 @@ pub struct Module {
 
   /// sources(from import or export statement) to avoid resolving a module twice
   pub src_to_resolved_id: HashMap<JsWord, SmolStr>,
+  /// sources of external modules, which are kept as imports instead of being bundled
+  pub external_sources: HashSet<JsWord>,
+}
+
+/// Collects sources of inline imports like `import("./foo").Foo` or `typeof import("./foo")`
+#[derive(Default)]
+struct InlineImportCollector {
+  sources: Vec<JsWord>,
+}
+
+impl Visit for InlineImportCollector {
+  fn visit_ts_import_type(&mut self, n: &TsImportType) {
+    self.sources.push(n.arg.value.clone());
+    n.visit_children_with(self);
+  }
 }
 
 pub struct ModuleOptions {
@@ -69,20 +88,29 @@ impl Module {
       imports: Default::default(),
       local_exports: Default::default(),
       src_to_resolved_id: Default::default(),
+      external_sources: Default::default(),
       exports: Default::default(),
     }
   }
 
-  pub fn pre_analyze_sub_modules(&mut self, swc_module: &swc_ecma_ast::Module) -> HashSet<SmolStr> {
+  pub fn pre_analyze_sub_modules(
+    &mut self,
+    swc_module: &swc_ecma_ast::Module,
+    externals: &Externals,
+  ) -> HashSet<SmolStr> {
     let mut discovered_import: HashSet<SmolStr> = Default::default();
 
+    // `import("./foo")` in types
+    let mut inline_import_collector = InlineImportCollector::default();
+    swc_module.visit_with(&mut inline_import_collector);
+
     let sub_modules = swc_module
       .body
       .iter()
       .filter_map(|module_item| {
         let mut discovered: Option<_> = None;
-        match module_item {
-          ModuleItem::ModuleDecl(module_decl) => match module_decl {
+        if let ModuleItem::ModuleDecl(module_decl) = module_item {
+          match module_decl {
             ModuleDecl::Import(import_decl) => {
               discovered = Some(import_decl.src.value.clone());
             }
@@ -100,23 +128,42 @@ impl Module {
               }
             }
             _ => (),
-          },
-          _ => {}
+          }
         }
 
-        if let Some(source) = discovered {
-          let resolved_id = resolve_id(
-            nodejs_path::resolve!(
-              nodejs_path::dirname(self.id.as_str()),
-              source.as_ref().to_string()
-            )
-            .as_str(),
-          );
+        discovered
+      })
+      .chain(inline_import_collector.sources)
+      .filter(|source| {
+        if externals.is_external(&self.id, source) {
+          self.external_sources.insert(source.clone());
+          return false;
+        }
+        true
+      })
+      .map(|source| {
+        let dir = nodejs_path::dirname(self.id.as_str());
 
-          return Some((source, resolved_id));
+        if let Some(resolved_id) = externals.resolve_self_reference(&source) {
+          return (source, resolved_id);
         }
 
-        None
+        if externals.is_inline(&source) {
+          if let Some(resolved_id) = resolve_package(&source, &dir) {
+            return (source, resolved_id);
+          }
+
+          log::warn!(
+            "[Module] unable to resolve declarations of package `{}` imported by {}",
+            source,
+            self.id
+          );
+        }
+
+        let resolved_id =
+          resolve_id(nodejs_path::resolve!(dir, source.as_ref().to_string()).as_str());
+
+        (source, resolved_id)
       })
       .collect::<Vec<_>>();
 
@@ -131,96 +178,134 @@ impl Module {
     discovered_import
   }
 
-  pub fn analyze(&mut self, swc_module: &mut swc_ecma_ast::Module) -> ModuleAnalyzer {
+  pub fn analyze(
+    &mut self,
+    swc_module: &mut swc_ecma_ast::Module,
+  ) -> Result<ModuleAnalyzer, Error> {
+    swc_module.body = split_var_decls(std::mem::take(&mut swc_module.body));
+
     let mut module_analyzer = ModuleAnalyzer::new();
     swc_module.visit_mut_with(&mut module_analyzer);
-    module_analyzer
+
+    match module_analyzer.error.take() {
+      Some(error) => Err(error.with_module(&self.id)),
+      None => Ok(module_analyzer),
+    }
   }
 
   pub fn generate_statements_from_ctxt(
     &mut self,
     swc_module: swc_ecma_ast::Module,
     statement_context: Vec<StatementContext>,
-  ) {
+  ) -> Result<(), Error> {
     use super::statement::{DeclStatement, ExportStatementNonDecl, ImportStatement};
-    let statements = swc_module
-      .body
-      .into_iter()
-      .zip(statement_context)
-      .map(|(swc_node, ctxt)| {
-        if ctxt.is_import {
-          Statement::ImportStatement(ImportStatement::new(swc_node))
-        } else if ctxt.is_export && !ctxt.is_export_decl {
-          Statement::ExportStatementNonDecl(ExportStatementNonDecl::new(swc_node))
-        } else {
-          let mut statement = DeclStatement::new(swc_node);
-          statement.reads = ctxt.reads;
-          statement.is_export_decl = ctxt.is_export_decl;
-          statement.mark = ctxt.mark.expect(
-            "[Module] `Mark` is supposed to be available in `StatementCtxt`, please file an issue",
-          );
-          statement.validate_node_type();
+    use swc_common::Spanned;
+    use swc_ecma_ast::Stmt;
 
-          Statement::DeclStatement(statement)
-        }
-      })
-      .collect::<Vec<_>>();
+    let mut statements = Vec::with_capacity(swc_module.body.len());
+
+    for (swc_node, ctxt) in swc_module.body.into_iter().zip(statement_context) {
+      if ctxt.is_import {
+        statements.push(Statement::ImportStatement(ImportStatement::new(swc_node)));
+      } else if ctxt.is_export && !ctxt.is_export_decl {
+        statements.push(Statement::ExportStatementNonDecl(
+          ExportStatementNonDecl::new(swc_node),
+        ));
+      } else if matches!(swc_node, ModuleItem::Stmt(Stmt::Empty(_))) {
+        // stray semicolons, e.g. `interface Foo {};`
+      } else {
+        // only declarations are bound to marks, other statements are not allowed in dts files
+        let mark = ctxt.mark.ok_or_else(|| {
+          let loc = SOURCE_MAP.lookup_char_pos(swc_node.span().lo);
+          Error::new_with_reason(
+            DtsupErrorType::UnsupportedSyntaxError,
+            &format!(
+              "[Module] unsupported statement at {}:{}:{}",
+              self.id,
+              loc.line,
+              loc.col_display + 1
+            ),
+          )
+        })?;
+
+        let mut statement = DeclStatement::new(swc_node);
+        statement.reads = ctxt.reads;
+        statement.is_export_decl = ctxt.is_export_decl;
+        statement.meaning = ctxt.meaning;
+        statement.has_side_effects = ctxt.has_side_effects;
+        statement.mark = mark;
+        statement.validate_node_type();
+
+        statements.push(Statement::DeclStatement(statement));
+      }
+    }
 
     self.statements = statements;
+
+    Ok(())
   }
 
-  pub fn include_statement_with_mark_set(&mut self, mark_set: &mut HashSet<Mark>) {
-    let mut mark_to_local_statement: HashMap<Mark, u32> = Default::default();
+  /// Include statements whose symbols are referenced in `mark_set` with the referenced meanings,
+  /// marks which are not declared in current module are added to `mark_set` for later modules.
+  pub fn include_statement_with_mark_set(&mut self, mark_set: &mut HashMap<Mark, Meaning>) {
+    // overloads and merged declarations share the same mark
+    let mut mark_to_local_statements: HashMap<Mark, Vec<u32>> = Default::default();
 
-    self
-      .statements
-      .iter()
-      .enumerate()
-      .for_each(|(index, s)| match s {
-        Statement::DeclStatement(s) => {
-          mark_to_local_statement.insert(s.mark, index as u32);
-        }
-        // import statement or non-declarative export statement are omitted
-        _ => (),
-      });
+    self.statements.iter().enumerate().for_each(|(index, s)| {
+      // import statement or non-declarative export statement are omitted
+      if let Statement::DeclStatement(s) = s {
+        mark_to_local_statements
+          .entry(s.mark)
+          .or_default()
+          .push(index as u32);
+      }
+    });
 
-    let mut maybe_local_reads: Vec<Mark> = Default::default();
-    let mut visited: HashSet<Mark> = Default::default();
+    let mut maybe_local_reads: Vec<(Mark, Meaning)> = Default::default();
+    let mut visited: HashMap<Mark, Meaning> = Default::default();
+
+    self.statements.iter().for_each(|s| {
+      if let Statement::DeclStatement(s) = s {
+        if s.has_side_effects {
+          maybe_local_reads.push((s.mark, s.meaning));
+          return;
+        }
 
-    self.statements.iter().for_each(|s| match s {
-      Statement::DeclStatement(s) => {
         let repr_mark = symbol::SYMBOL_BOX.lock().find_root(s.mark);
-        if mark_set.contains(&repr_mark) {
-          maybe_local_reads.push(s.mark);
+        if let Some(meaning) = mark_set.get(&repr_mark) {
+          maybe_local_reads.push((s.mark, *meaning));
         }
       }
-      _ => (),
     });
 
-    while let Some(maybe_local_mark) = maybe_local_reads.pop() {
-      if visited.contains(&maybe_local_mark) {
+    while let Some((maybe_local_mark, meaning)) = maybe_local_reads.pop() {
+      let visited_meaning = visited.entry(maybe_local_mark).or_default();
+      if visited_meaning.contains(meaning) {
         continue;
       }
-      visited.insert(maybe_local_mark);
+      *visited_meaning |= meaning;
+
+      // if mark is located in current module, then include statements declaring the meaning.
+      if let Some(indexes) = mark_to_local_statements.get(&maybe_local_mark) {
+        indexes.iter().for_each(|index| {
+          if let Statement::DeclStatement(s) = &mut self.statements[*index as usize] {
+            if !s.meaning.intersects(meaning) {
+              return;
+            }
 
-      // if mark is located in current module, then include it.
-      if let Some(index) = mark_to_local_statement.get(&maybe_local_mark) {
-        match &mut self.statements[*index as usize] {
-          Statement::DeclStatement(s) => {
             log::debug!(
               "[Module] including statement with mark {:?} \nstatement: {:?}",
               s.mark,
               s,
             );
             s.include();
-            maybe_local_reads.extend(&s.reads);
+            maybe_local_reads.extend(s.reads.iter().map(|(mark, meaning)| (*mark, *meaning)));
           }
-          _ => (),
-        }
+        });
       } else {
         // if not, we find the repr mark, and let it be discovered in later visits.
         let repr_mark = symbol::SYMBOL_BOX.lock().find_root(maybe_local_mark);
-        mark_set.insert(repr_mark);
+        *mark_set.entry(repr_mark).or_default() |= meaning;
       }
     }
   }
@@ -1,19 +1,30 @@
-use std::collections::{hash_map, HashMap, HashSet};
+use std::collections::{hash_map, HashMap};
 
 use swc_atoms::{js_word, JsWord};
 use swc_common::Mark;
-use swc_ecma_ast::{Decl, ExportSpecifier, Pat, TsType, TsTypeElement};
-use swc_ecma_visit::{noop_visit_mut_type, VisitMut, VisitMutWith};
+use swc_ecma_ast::Ident;
+use swc_ecma_visit::{VisitMut, VisitMutWith};
 
 use super::module::{ImportIdent, Source};
 use super::{
-  scope::{Definition, Scope, ScopeKind, VariableDeclaration},
+  parse::SOURCE_MAP,
+  scope::{Meaning, Scope, ScopeKind, VariableDeclaration},
   symbol::{self, MarkExt, SyntaxContextExt},
-  utils::{get_module_export_name, mark_module_export_name},
+  utils::{
+    export_equals_name, get_leftmost_entity_ident_mut, get_leftmost_ident_mut,
+    get_module_export_name, get_namespace_ident_name, is_instantiated_namespace,
+    mark_module_export_name,
+  },
 };
+use crate::result::{DtsupErrorType, Error};
 
 type LocalName = JsWord;
 
+#[inline]
+fn export_default_ident() -> Ident {
+  Ident::new("export_default".into(), Default::default())
+}
+
 #[derive(Debug)]
 pub struct ModuleImport {
   /// Index of the same `src` shares the same index
@@ -24,10 +35,29 @@ pub struct ModuleImport {
   pub local_name: LocalName,
   /// `a` in `{ a as b }`
   pub original_ident: ImportIdent,
+  /// `import type { a }` or `import { type a }`
+  pub is_type_only: bool,
+  /// `import("./foo").a` in types
+  pub is_inline: bool,
   /// Source(of importee) may be the same since we will split single statement with multiple variables into different imports for **tree-shaking**
   pub src: Source,
 }
 
+impl ModuleImport {
+  /// Name of the identifiers referencing the import, local names of inline imports are synthesized,
+  /// so they are referenced by the imported name or the name generated from the source.
+  pub fn binding_name(&self) -> JsWord {
+    if !self.is_inline {
+      return self.local_name.clone();
+    }
+
+    match &self.original_ident {
+      ImportIdent::Name(name) => name.clone(),
+      ImportIdent::Namespace => get_namespace_ident_name(&self.src),
+    }
+  }
+}
+
 #[derive(Debug, Clone)]
 pub struct ModuleExportName {
   pub exported_name: LocalName,
@@ -37,6 +67,8 @@ pub struct ModuleExportName {
   pub src: Option<Source>,
   // index for locating import/export order, only available for situation in which `src` is `some`
   pub index: Option<u32>,
+  // `export type { name }` or `export { type name }`
+  pub is_type_only: bool,
 }
 
 #[derive(Debug, Clone)]
@@ -69,11 +101,19 @@ pub struct StatementContext {
   pub is_import: bool,
   pub is_export: bool,
   // IMPORTANT:
-  // export declarations like `export declare type Foo = string` or export namespaced are declarations,
-  // which is supposed to be transformed(`export declare` -> `declare`, or `export * as foo from "./foo"` -> declare namespace "foo") and generated
+  // export declarations like `export declare type Foo = string` are declarations,
+  // which is supposed to be transformed(`export declare` -> `declare`) and generated,
+  // while `export * as foo from "./foo"` is generated as a namespace of the module after linking
   pub is_export_decl: bool,
 
-  pub reads: HashSet<Mark>,
+  pub reads: HashMap<Mark, Meaning>,
+
+  // `declare global {}` and `declare module "foo" {}` augment other modules,
+  // they are always included as long as the module is bundled
+  pub has_side_effects: bool,
+
+  // Meanings declared by the statement, merged declarations in different statements may declare different meanings
+  pub meaning: Meaning,
 
   // Tree-shaking includes statement with its mark
   // `None` if `is_import` or `is_export` equals to `true`
@@ -91,6 +131,8 @@ pub struct ModuleAnalyzer {
   pub imports: HashMap<LocalName, ModuleImport>,
   pub exports: Vec<ModuleExport>,
   pub statement_context: Vec<StatementContext>,
+  /// The first error occurred in analyzing, e.g. conflicting declarations
+  pub error: Option<Error>,
 }
 
 impl ModuleAnalyzer {
@@ -102,6 +144,7 @@ impl ModuleAnalyzer {
       imports: Default::default(),
       exports: Default::default(),
       statement_context: Default::default(),
+      error: None,
     }
   }
 
@@ -117,12 +160,6 @@ impl ModuleAnalyzer {
     self.current_statement_index += 1;
   }
 
-  fn get_current_statement(&self) -> Option<&StatementContext> {
-    self
-      .statement_context
-      .get(self.current_statement_index as usize)
-  }
-
   // It seems not necessary since we've already done this in `add_variable_read`
   // fn sync_current_scope_reads_to_statement(&mut self) {
   //   let scope_reads = self.get_current_scope().unwrap().get_reads().clone();
@@ -136,39 +173,25 @@ impl ModuleAnalyzer {
       .get_mut(self.current_statement_index as usize)
   }
 
-  fn pop_scope_on_type_param(&mut self) {
-    if matches!(
-      self.get_current_scope_kind(),
-      Some(&ScopeKind::TsTypeParameter)
-    ) {
-      self.pop_scope();
-    }
-  }
-
   fn advance_import_index(&mut self) {
     self.current_import_index += 1;
   }
 
-  fn get_current_scope(&self) -> Option<&Scope> {
-    self.scope.last()
-  }
-
   fn get_current_scope_mut(&mut self) -> Option<&mut Scope> {
     self.scope.last_mut()
   }
 
-  fn get_current_scope_kind(&self) -> Option<&ScopeKind> {
-    self.scope.last().map(|s| &s.kind)
-  }
-
-  fn add_variable_read(&mut self, name: &JsWord) -> Option<Mark> {
-    if let Some(mark) = self.get_mark_by_name(name) {
-      let ctxt = self.get_current_statement_mut().unwrap();
-      ctxt.reads.insert(mark.clone());
+  fn add_mark_read(&mut self, mark: Mark, meaning: Meaning) {
+    let ctxt = self.get_current_statement_mut().unwrap();
+    *ctxt.reads.entry(mark).or_default() |= meaning;
 
-      let scope = self.get_current_scope_mut().unwrap();
-      scope.add_variable_read(mark.clone());
+    let scope = self.get_current_scope_mut().unwrap();
+    scope.add_variable_read(mark, meaning);
+  }
 
+  fn add_variable_read(&mut self, name: &JsWord, meaning: Meaning) -> Option<Mark> {
+    if let Some(mark) = self.get_mark_by_name(name, meaning) {
+      self.add_mark_read(mark, meaning);
       return Some(mark);
     }
 
@@ -177,31 +200,210 @@ impl ModuleAnalyzer {
     None
   }
 
+  /// Read the variable referenced by `ident` in the meaning space of `meaning`
+  /// and mark the identifier with the variable's mark
+  fn read_ident(&mut self, ident: &mut Ident, meaning: Meaning) {
+    if let Some(mark) = self.add_variable_read(&ident.sym, meaning) {
+      ident.span.ctxt = mark.as_ctxt();
+    }
+  }
+
+  /// Bind the identifier of a declaration to a new mark,
+  /// for top-level declarations, the mark will be used as the mark of current statement for tree-shaking.
+  /// Overloads and merged declarations share the mark of the first declaration.
+  fn declare_statement(&mut self, ident: &mut Ident, definition_type: VariableDeclaration) -> Mark {
+    let meaning = definition_type.meaning();
+    let mark = self.add_variable_definition(ident.sym.clone(), definition_type, symbol::new_mark());
+    ident.span.ctxt = mark.as_ctxt();
+
+    // declarations in namespaces are parts of the namespace statement
+    if self.is_top_level() {
+      let ctxt = self.get_current_statement_mut().unwrap();
+      ctxt.mark = Some(mark);
+      ctxt.meaning |= meaning;
+    }
+
+    mark
+  }
+
+  /// `infer U` is declared in the nearest conditional type,
+  /// even if it is nested in other types like `T extends { foo: infer U } ? U : never`
+  fn declare_infer_type(&mut self, type_param: &mut swc_ecma_ast::TsTypeParam) {
+    if let Some(scope) = self
+      .scope
+      .iter_mut()
+      .rev()
+      .find(|scope| matches!(scope.kind, ScopeKind::TsConditionalType))
+    {
+      // the same name can be inferred multiple times, e.g. `T extends [infer U, infer U] ? U : never`
+      // type parameters are only declared in the type meaning space, which is checked above
+      let mark = match scope.get_variable_definition(&type_param.name.sym, Meaning::TYPE) {
+        Some(def) => def.mark,
+        None => {
+          let new_mark = symbol::new_mark();
+          scope
+            .add_variable_definition(
+              type_param.name.sym.clone(),
+              VariableDeclaration::TsTypeParameter,
+              new_mark,
+            )
+            .unwrap_or(new_mark)
+        }
+      };
+      type_param.name.span.ctxt = mark.as_ctxt();
+    }
+
+    // `infer U extends string`
+    type_param.constraint.visit_mut_with(self);
+  }
+
+  #[inline]
+  fn is_top_level(&self) -> bool {
+    self.scope.len() == 1
+  }
+
+  /// Declarations are hoisted, names are available before the statements declaring them,
+  /// e.g. `interface Foo { bar: Bar }` followed by `interface Bar {}`
+  fn hoist_module_item(&mut self, n: &mut swc_ecma_ast::ModuleItem) {
+    use swc_ecma_ast::{
+      ClassExpr, DefaultDecl, ExportDecl, FnExpr, ModuleDecl, ModuleItem, Stmt, TsModuleRef,
+    };
+
+    match n {
+      ModuleItem::Stmt(Stmt::Decl(decl))
+      | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. })) => {
+        self.hoist_decl(decl);
+      }
+      ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export_default)) => {
+        // `export default class Foo {}` or `export default function foo()` declares `foo` in current module,
+        // unnamed default exports are named as `export_default` to be declared after `export` is stripped
+        match &mut export_default.decl {
+          DefaultDecl::Class(ClassExpr { ident, .. }) => {
+            let ident = ident.get_or_insert_with(export_default_ident);
+            self.declare_statement(ident, VariableDeclaration::ClassDeclaration);
+          }
+          DefaultDecl::Fn(FnExpr { ident, .. }) => {
+            let ident = ident.get_or_insert_with(export_default_ident);
+            self.declare_statement(ident, VariableDeclaration::FunctionDeclaration);
+          }
+          DefaultDecl::TsInterfaceDecl(interface) => {
+            self.declare_statement(
+              &mut interface.id,
+              VariableDeclaration::TsInterfaceDeclaration,
+            );
+          }
+        }
+      }
+      // `import Bar = Foo.Bar` declares an alias, while `import Foo = require("./foo")` is an import
+      ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(import_equals))
+        if matches!(import_equals.module_ref, TsModuleRef::TsEntityName(_)) =>
+      {
+        self.declare_statement(
+          &mut import_equals.id,
+          VariableDeclaration::TsImportEqualsDeclaration,
+        );
+      }
+      _ => (),
+    }
+  }
+
+  /// `Foo` in `import Bar = Foo.Bar` is a namespace
+  fn read_ts_module_ref(&mut self, module_ref: &mut swc_ecma_ast::TsModuleRef) {
+    if let swc_ecma_ast::TsModuleRef::TsEntityName(entity_name) = module_ref {
+      let meaning = if matches!(entity_name, swc_ecma_ast::TsEntityName::Ident(_)) {
+        Meaning::ALL
+      } else {
+        Meaning::NAMESPACE
+      };
+      self.read_ident(get_leftmost_entity_ident_mut(entity_name), meaning);
+    }
+  }
+
+  fn hoist_decl(&mut self, n: &mut swc_ecma_ast::Decl) {
+    use swc_ecma_ast::{Decl, Pat, TsModuleName};
+
+    match n {
+      Decl::Class(c) => {
+        self.declare_statement(&mut c.ident, VariableDeclaration::ClassDeclaration);
+      }
+      Decl::Fn(f) => {
+        self.declare_statement(&mut f.ident, VariableDeclaration::FunctionDeclaration);
+      }
+      Decl::Var(v) => v.decls.iter_mut().for_each(|decl| match &mut decl.name {
+        Pat::Ident(ident) => {
+          self.declare_statement(&mut ident.id, VariableDeclaration::VariableDeclaration);
+        }
+        p => {
+          log::warn!(
+            "[ModuleAnalyzer] Pattern type {:?} should not available in dts file",
+            p
+          )
+        }
+      }),
+      Decl::TsInterface(t) => {
+        self.declare_statement(&mut t.id, VariableDeclaration::TsInterfaceDeclaration);
+      }
+      Decl::TsTypeAlias(t) => {
+        self.declare_statement(&mut t.id, VariableDeclaration::TsTypeAliasDeclaration);
+      }
+      Decl::TsEnum(t) => {
+        self.declare_statement(&mut t.id, VariableDeclaration::TsEnumDeclaration);
+      }
+      Decl::TsModule(t) => match &mut t.id {
+        // `declare global` and `declare module "foo"` are not bound to any variables
+        TsModuleName::Ident(ident) if !t.global => {
+          let is_instantiated = is_instantiated_namespace(t.body.as_ref());
+          self.declare_statement(
+            ident,
+            VariableDeclaration::TsNamespaceDeclaration { is_instantiated },
+          );
+        }
+        _ => {
+          if self.is_top_level() {
+            let ctxt = self.get_current_statement_mut().unwrap();
+            ctxt.mark = Some(symbol::new_mark());
+            ctxt.meaning = Meaning::ALL;
+            ctxt.has_side_effects = true;
+          }
+        }
+      },
+    }
+  }
+
   fn add_variable_definition(
     &mut self,
     name: JsWord,
     definition_type: VariableDeclaration,
     new_mark: Mark,
-  ) {
+  ) -> Mark {
     let scope = self.get_current_scope_mut().unwrap();
-    scope.add_variable_definition(name, definition_type, new_mark);
+    match scope.add_variable_definition(name, definition_type, new_mark) {
+      Ok(mark) => mark,
+      Err(error) => {
+        // keep analyzing with the new mark, only the first error is reported
+        self.error.get_or_insert(error);
+        new_mark
+      }
+    }
   }
 
   fn get_top_level_names(&self) -> Vec<JsWord> {
     let mut top_level_names = self.imports.keys().cloned().collect::<Vec<JsWord>>();
 
     if let Some(top_level_scope) = self.scope.first() {
-      top_level_names.extend(top_level_scope.definitions.keys().cloned());
+      top_level_names.extend(top_level_scope.get_names().cloned());
     }
 
     top_level_names
   }
 
+  /// Resolve `name` in the meaning space of `meaning`,
+  /// imports are resolved in all meaning spaces since meanings of imported symbols are unknown before linking.
   #[inline]
-  fn get_mark_by_name(&self, name: &JsWord) -> Option<Mark> {
+  fn get_mark_by_name(&self, name: &JsWord, meaning: Meaning) -> Option<Mark> {
     // reverse iterate over scopes to find if `name` is available
     for scope in self.scope.iter().rev() {
-      if let Some(def) = scope.get_variable_definition(name) {
+      if let Some(def) = scope.get_variable_definition(name, meaning) {
         return Some(def.mark);
       }
     }
@@ -209,15 +411,76 @@ impl ModuleAnalyzer {
     // or iterate over imports
     self.imports.iter().find_map(|(imported, module_import)| {
       if imported == name {
-        Some(module_import.mark.clone())
+        Some(module_import.mark)
       } else {
         None
       }
     })
   }
 
+  /// `import("./foo").Bar.Baz` refers to `Bar.Baz` with `import { Bar } from "./foo"`,
+  /// and `import("foo")` refers to the namespace of `import * as foo from "foo"`,
+  /// the import is bound with a synthesized local name which is never able to be referenced by identifiers.
+  /// Returns the entity name to replace the import type, since the binding will be available after bundling.
+  fn add_inline_import(
+    &mut self,
+    import_type: &mut swc_ecma_ast::TsImportType,
+    meaning: Meaning,
+  ) -> swc_ecma_ast::TsEntityName {
+    use swc_ecma_ast::TsEntityName;
+
+    let src = import_type.arg.value.clone();
+
+    let (local_name, original_ident, mut entity_name) = match import_type.qualifier.take() {
+      Some(mut qualifier) => {
+        let name = get_leftmost_entity_ident_mut(&mut qualifier).sym.clone();
+        (
+          format!("import(\"{}\").{}", src, name),
+          ImportIdent::Name(name),
+          qualifier,
+        )
+      }
+      None => (
+        format!("import(\"{}\")", src),
+        ImportIdent::Namespace,
+        TsEntityName::Ident(Ident::new(
+          get_namespace_ident_name(&src),
+          import_type.arg.span,
+        )),
+      ),
+    };
+    let local_name: LocalName = local_name.into();
+
+    let mark = match self.imports.get(&local_name) {
+      Some(module_import) => module_import.mark,
+      None => {
+        let mark = symbol::new_mark();
+        self.imports.insert(
+          local_name.clone(),
+          ModuleImport {
+            index: self.current_import_index,
+            mark,
+            local_name,
+            original_ident,
+            src,
+            // `import("./foo")` may be used in `typeof`
+            is_type_only: false,
+            is_inline: true,
+          },
+        );
+        self.advance_import_index();
+        mark
+      }
+    };
+
+    get_leftmost_entity_ident_mut(&mut entity_name).span.ctxt = mark.as_ctxt();
+    self.add_mark_read(mark, meaning);
+
+    entity_name
+  }
+
   fn add_import(&mut self, import_decl: &mut swc_ecma_ast::ImportDecl) {
-    use swc_ecma_ast::{ImportSpecifier, ModuleExportName};
+    use swc_ecma_ast::ImportSpecifier;
 
     let index = self.current_import_index;
     let src = import_decl.src.value.clone();
@@ -225,21 +488,17 @@ impl ModuleAnalyzer {
     let mut original_idents: Vec<ImportIdent> = Vec::with_capacity(len);
     let mut local_names: Vec<JsWord> = Vec::with_capacity(len);
     let mut marks: Vec<Mark> = Vec::with_capacity(len);
+    let mut type_only_flags: Vec<bool> = Vec::with_capacity(len);
 
     import_decl.specifiers.iter_mut().for_each(|s| match s {
       ImportSpecifier::Named(s) => {
         local_names.push(s.local.sym.clone());
+        type_only_flags.push(import_decl.type_only || s.is_type_only);
 
         // import { foo as bar } from "./foo", imported: `foo`, local: `bar`
+        // import { "foo-bar" as bar } from "./foo", imported: `"foo-bar"`, local: `bar`
         if let Some(n) = &s.imported {
-          match n {
-            ModuleExportName::Ident(i) => {
-              original_idents.push(ImportIdent::Name(i.sym.clone()));
-            }
-            ModuleExportName::Str(_) => {
-              // https://tc39.es/ecma262/#prod-ModuleExportName
-            }
-          }
+          original_idents.push(ImportIdent::Name(get_module_export_name(n)));
         } else {
           // import { foo } from "./foo", imported: None, local: `foo`
           original_idents.push(ImportIdent::Name(s.local.sym.clone()))
@@ -251,6 +510,7 @@ impl ModuleAnalyzer {
       }
       ImportSpecifier::Default(s) => {
         local_names.push(s.local.sym.clone());
+        type_only_flags.push(import_decl.type_only);
         original_idents.push(ImportIdent::Name(js_word!("default")));
 
         let new_mark = symbol::new_mark();
@@ -259,6 +519,7 @@ impl ModuleAnalyzer {
       }
       ImportSpecifier::Namespace(s) => {
         local_names.push(s.local.sym.clone());
+        type_only_flags.push(import_decl.type_only);
         original_idents.push(ImportIdent::Namespace);
 
         let new_mark = symbol::new_mark();
@@ -271,12 +532,17 @@ impl ModuleAnalyzer {
       .into_iter()
       .zip(original_idents)
       .zip(marks)
-      .for_each(|((local_name, original_ident), mark)| {
+      .zip(type_only_flags)
+      .for_each(|(((local_name, original_ident), mark), is_type_only)| {
         if self.get_top_level_names().contains(&local_name) {
-          panic!(
-            "[ModuleAnalyzer] duplicated variable detected {}",
-            local_name
-          );
+          self.error.get_or_insert(Error::new_with_reason(
+            DtsupErrorType::DuplicateDeclarationError,
+            &format!(
+              "[ModuleAnalyzer] unable to import `{}`, it has already been declared",
+              local_name
+            ),
+          ));
+          return;
         }
 
         if let hash_map::Entry::Vacant(entry) = self.imports.entry(local_name.clone()) {
@@ -286,6 +552,8 @@ impl ModuleAnalyzer {
             local_name,
             original_ident,
             src: src.clone(),
+            is_type_only,
+            is_inline: false,
           };
           entry.insert(module_import);
         }
@@ -304,7 +572,26 @@ impl VisitMut for ModuleAnalyzer {
         ctxt.index = index as u32;
       });
 
+    n.body
+      .iter_mut()
+      .enumerate()
+      .for_each(|(index, module_item)| {
+        self.current_statement_index = index as u32;
+        self.hoist_module_item(module_item);
+      });
+    self.current_statement_index = 0;
+
     n.visit_mut_children_with(self);
+
+    // `import type { Foo } from "./foo"; export { Foo }` only exports the type side of `Foo`
+    let imports = &self.imports;
+    self.exports.iter_mut().for_each(|export| {
+      if let ModuleExport::Name(e) = export {
+        if e.src.is_none() && matches!(imports.get(&e.original_ident), Some(i) if i.is_type_only) {
+          e.is_type_only = true;
+        }
+      }
+    });
   }
 
   fn visit_mut_module_item(&mut self, n: &mut swc_ecma_ast::ModuleItem) {
@@ -312,122 +599,291 @@ impl VisitMut for ModuleAnalyzer {
     self.advance_statement();
   }
 
-  // TODO: support more declarations
+  // Identifiers of declarations have already been bound in hoisting
 
   fn visit_mut_fn_decl(&mut self, n: &mut swc_ecma_ast::FnDecl) {
-    n.function.type_params.visit_mut_with(self);
-    // n.visit_mut_children_with(self);
+    n.function.visit_mut_with(self);
+  }
+
+  fn visit_mut_function(&mut self, n: &mut swc_ecma_ast::Function) {
+    // type parameters are available in params and the return type of the function
+    n.type_params.visit_mut_with(self);
 
-    self.pop_scope_on_type_param();
+    // includes `this` parameters, rest parameters and destructured parameters with type annotations,
+    // parameter names are bindings of the function, so they are not going to be read
+    n.params.visit_mut_with(self);
+    n.return_type.visit_mut_with(self);
+
+    if n.type_params.is_some() {
+      self.pop_scope();
+    }
   }
 
   fn visit_mut_class_decl(&mut self, n: &mut swc_ecma_ast::ClassDecl) {
-    n.class.type_params.visit_mut_with(self);
+    n.class.visit_mut_with(self);
   }
 
-  fn visit_mut_ts_type_ann(&mut self, n: &mut swc_ecma_ast::TsTypeAnn) {
-    // already included in `TsType` visitor
-    n.visit_mut_children_with(self);
+  fn visit_mut_class(&mut self, n: &mut swc_ecma_ast::Class) {
+    // `extends` is evaluated outside of class type parameters,
+    // e.g. `class Foo<T> extends Mixin(Base)<T>`, only `super_type_params` are able to access `T`
+    n.super_class.visit_mut_with(self);
+
+    n.type_params.visit_mut_with(self);
+
+    n.super_type_params.visit_mut_with(self);
+    n.implements.visit_mut_with(self);
+
+    n.body.visit_mut_with(self);
+
+    if n.type_params.is_some() {
+      self.pop_scope();
+    }
   }
 
-  fn visit_mut_ts_type_alias_decl(&mut self, n: &mut swc_ecma_ast::TsTypeAliasDecl) {
-    use swc_ecma_ast::TsTypeAliasDecl;
+  fn visit_mut_constructor(&mut self, n: &mut swc_ecma_ast::Constructor) {
+    // `body` is not available in dts files
+    n.key.visit_mut_with(self);
+    n.params.visit_mut_with(self);
+  }
 
-    let new_mark = symbol::new_mark();
-    n.id.span.ctxt = new_mark.as_ctxt();
+  fn visit_mut_ts_expr_with_type_args(&mut self, n: &mut swc_ecma_ast::TsExprWithTypeArgs) {
+    // `implements Foo.Bar<T>` or interface's `extends Foo.Bar<T>`,
+    // only the leftmost identifier `Foo` is supposed to be read
+    // `Foo` is a type, while `Foo` in `Foo.Bar` is a namespace
+    let meaning = if matches!(*n.expr, swc_ecma_ast::Expr::Ident(_)) {
+      Meaning::TYPE
+    } else {
+      Meaning::NAMESPACE
+    };
+
+    if let Some(ident) = get_leftmost_ident_mut(&mut n.expr) {
+      self.read_ident(ident, meaning);
+    }
 
-    let ctxt = self.get_current_statement_mut().unwrap();
-    ctxt.mark = Some(new_mark.clone());
+    n.type_args.visit_mut_with(self);
+  }
+
+  fn visit_mut_expr(&mut self, n: &mut swc_ecma_ast::Expr) {
+    use swc_ecma_ast::Expr;
 
-    self.add_variable_definition(
-      n.id.sym.clone(),
-      VariableDeclaration::TsTypeAliasDeclaration,
-      new_mark,
-    );
+    match n {
+      Expr::Ident(ident) => {
+        self.read_ident(ident, Meaning::VALUE);
+      }
+      _ => n.visit_mut_children_with(self),
+    }
+  }
+
+  fn visit_mut_member_expr(&mut self, n: &mut swc_ecma_ast::MemberExpr) {
+    use swc_ecma_ast::MemberProp;
+
+    // property of `Foo.bar` is not a variable
+    n.obj.visit_mut_with(self);
+
+    if let MemberProp::Computed(computed) = &mut n.prop {
+      computed.visit_mut_with(self);
+    }
+  }
 
+  fn visit_mut_ts_type_ann(&mut self, n: &mut swc_ecma_ast::TsTypeAnn) {
+    // already included in `TsType` visitor
+    n.visit_mut_children_with(self);
+  }
+
+  fn visit_mut_ts_type_alias_decl(&mut self, n: &mut swc_ecma_ast::TsTypeAliasDecl) {
     n.type_params.visit_mut_with(self);
 
     self.push_scope(Scope::new(ScopeKind::TypeScope));
     n.type_ann.visit_mut_with(self);
     self.pop_scope();
 
-    self.pop_scope_on_type_param();
+    if n.type_params.is_some() {
+      self.pop_scope();
+    }
   }
 
   fn visit_mut_ts_type_element(&mut self, n: &mut swc_ecma_ast::TsTypeElement) {
-    use swc_ecma_ast::{Expr, TsTypeElement};
+    use swc_ecma_ast::TsTypeElement;
+    // keys are not variables unless they are computed, e.g. `[Symbol.iterator]`
     match n {
-      TsTypeElement::TsPropertySignature(prop) => {
-        prop.type_ann.visit_mut_with(self);
+      TsTypeElement::TsCallSignatureDecl(t) => {
+        t.type_params.visit_mut_with(self);
+        t.params.visit_mut_with(self);
+        t.type_ann.visit_mut_with(self);
+
+        if t.type_params.is_some() {
+          self.pop_scope();
+        }
       }
-      _ => {
-        // temporarily not support
+      TsTypeElement::TsConstructSignatureDecl(t) => {
+        t.type_params.visit_mut_with(self);
+        t.params.visit_mut_with(self);
+        t.type_ann.visit_mut_with(self);
+
+        if t.type_params.is_some() {
+          self.pop_scope();
+        }
+      }
+      TsTypeElement::TsPropertySignature(t) => {
+        if t.computed {
+          t.key.visit_mut_with(self);
+        }
+
+        t.type_params.visit_mut_with(self);
+        t.params.visit_mut_with(self);
+        t.type_ann.visit_mut_with(self);
+
+        if t.type_params.is_some() {
+          self.pop_scope();
+        }
+      }
+      TsTypeElement::TsGetterSignature(t) => {
+        if t.computed {
+          t.key.visit_mut_with(self);
+        }
+
+        t.type_ann.visit_mut_with(self);
+      }
+      TsTypeElement::TsSetterSignature(t) => {
+        if t.computed {
+          t.key.visit_mut_with(self);
+        }
+
+        t.param.visit_mut_with(self);
+      }
+      TsTypeElement::TsMethodSignature(t) => {
+        if t.computed {
+          t.key.visit_mut_with(self);
+        }
+
+        t.type_params.visit_mut_with(self);
+        t.params.visit_mut_with(self);
+        t.type_ann.visit_mut_with(self);
+
+        if t.type_params.is_some() {
+          self.pop_scope();
+        }
+      }
+      TsTypeElement::TsIndexSignature(t) => {
+        // `[key: string]: Foo`, `key` is not a variable
+        t.params.visit_mut_with(self);
+        t.type_ann.visit_mut_with(self);
       }
     }
   }
 
-  fn visit_mut_ts_interface_decl(&mut self, n: &mut swc_ecma_ast::TsInterfaceDecl) {
-    n.type_params.visit_mut_with(self);
+  fn visit_mut_ts_enum_decl(&mut self, n: &mut swc_ecma_ast::TsEnumDecl) {
+    use swc_ecma_ast::TsEnumMemberId;
 
-    let new_mark = symbol::new_mark();
-    n.id.span.ctxt = new_mark.as_ctxt();
+    // members are available in initializers without qualifications, e.g. `enum Foo { A = 1, B = A << 1 }`
+    self.push_scope(Scope::new(ScopeKind::TypeScope));
 
-    let ctxt = self.get_current_statement_mut().unwrap();
-    ctxt.mark = Some(new_mark.clone());
+    n.members.iter_mut().for_each(|member| {
+      if let TsEnumMemberId::Ident(ident) = &mut member.id {
+        let new_mark = self.add_variable_definition(
+          ident.sym.clone(),
+          VariableDeclaration::VariableDeclaration,
+          symbol::new_mark(),
+        );
+        ident.span.ctxt = new_mark.as_ctxt();
+      }
+    });
 
-    let scope = self.get_current_scope_mut().unwrap();
-    scope.add_variable_definition(
-      n.id.sym.clone(),
-      VariableDeclaration::TsInterfaceDeclaration,
-      new_mark,
-    );
+    // initializers may reference other enums or constants, e.g. `enum Foo { A = Bar.A }`
+    n.members.iter_mut().for_each(|member| {
+      member.init.visit_mut_with(self);
+    });
+
+    self.pop_scope();
+  }
 
+  fn visit_mut_ts_module_decl(&mut self, n: &mut swc_ecma_ast::TsModuleDecl) {
     self.push_scope(Scope::new(ScopeKind::TypeScope));
+    n.body.visit_mut_with(self);
+    self.pop_scope();
+  }
+
+  fn visit_mut_ts_namespace_decl(&mut self, n: &mut swc_ecma_ast::TsNamespaceDecl) {
+    // `Bar` in `namespace Foo.Bar {}` is a member of `Foo`, instead of a variable in current scope
+    self.push_scope(Scope::new(ScopeKind::TypeScope));
+    n.body.visit_mut_with(self);
+    self.pop_scope();
+  }
 
-    n.extends.iter_mut().for_each(|extend| {
-      use swc_ecma_ast::Expr;
-      match extend.expr.as_mut() {
-        Expr::Ident(ident) => {
-          let mut mark = self.add_variable_read(&ident.sym);
-          if let Some(mark) = mark.as_ref() {
-            ident.span.ctxt = mark.as_ctxt();
+  fn visit_mut_ts_module_block(&mut self, n: &mut swc_ecma_ast::TsModuleBlock) {
+    use swc_ecma_ast::{ExportSpecifier, ModuleDecl, ModuleExportName, ModuleItem, NamedExport};
+
+    n.body
+      .iter_mut()
+      .for_each(|module_item| self.hoist_module_item(module_item));
+
+    // module items in namespaces are parts of the namespace statement,
+    // they should not be treated as the imports or exports of current module
+    n.body.iter_mut().for_each(|module_item| match module_item {
+      ModuleItem::Stmt(stmt) => stmt.visit_mut_with(self),
+      ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+        export_decl.decl.visit_mut_with(self);
+      }
+      ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(import_equals)) => {
+        self.read_ts_module_ref(&mut import_equals.module_ref);
+      }
+      ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport {
+        specifiers,
+        src: None,
+        ..
+      })) => {
+        // `export { Foo as Bar }` in namespaces exports variables in scope
+        specifiers.iter_mut().for_each(|s| {
+          if let ExportSpecifier::Named(named) = s {
+            if let ModuleExportName::Ident(ident) = &mut named.orig {
+              self.read_ident(ident, Meaning::ALL);
+            }
           }
-        }
-        _ => {
-          // currently we don't support these
-        }
+        });
       }
+      _ => (),
     });
+  }
 
+  fn visit_mut_ts_interface_decl(&mut self, n: &mut swc_ecma_ast::TsInterfaceDecl) {
+    n.type_params.visit_mut_with(self);
+
+    self.push_scope(Scope::new(ScopeKind::TypeScope));
+
+    n.extends.visit_mut_with(self);
     n.body.visit_mut_with(self);
 
     self.pop_scope();
-    self.pop_scope_on_type_param();
+
+    if n.type_params.is_some() {
+      self.pop_scope();
+    }
   }
 
+  /// Pushes a scope of type parameters, which is supposed to be popped by the owner of type parameters
   fn visit_mut_ts_type_param_decl(&mut self, n: &mut swc_ecma_ast::TsTypeParamDecl) {
     self.push_scope(Scope::new(ScopeKind::TsTypeParameter));
 
-    if let Some(scope) = self.get_current_scope_mut() {
-      n.params.iter_mut().for_each(|param| {
-        let new_mark = symbol::new_mark();
-        param.span.ctxt = new_mark.as_ctxt();
-
-        scope.add_variable_definition(
-          param.name.sym.clone(),
-          VariableDeclaration::TsTypeParameter,
-          new_mark,
-        );
-
-        // `param.default` is visited with `visit_mut_ts_type`
-      });
-    }
+    // all parameters are declared before constraints and defaults are visited,
+    // since siblings are available in each other's constraints and defaults, e.g. `<T, K extends keyof T>`
+    n.params.iter_mut().for_each(|param| {
+      let new_mark = self.add_variable_definition(
+        param.name.sym.clone(),
+        VariableDeclaration::TsTypeParameter,
+        symbol::new_mark(),
+      );
+      param.span.ctxt = new_mark.as_ctxt();
+      param.name.span.ctxt = new_mark.as_ctxt();
+    });
 
-    n.visit_mut_children_with(self);
+    n.params.iter_mut().for_each(|param| {
+      param.constraint.visit_mut_with(self);
+      param.default.visit_mut_with(self);
+    });
   }
 
   fn visit_mut_module_decl(&mut self, n: &mut swc_ecma_ast::ModuleDecl) {
-    use swc_ecma_ast::{ImportDecl, ModuleDecl};
+    use swc_ecma_ast::ModuleDecl;
     match n {
       ModuleDecl::Import(import_decl) => {
         self.add_import(import_decl);
@@ -448,8 +904,9 @@ impl VisitMut for ModuleAnalyzer {
           Decl::Var(v) => {
             v.visit_mut_with(self);
 
-            v.decls.iter_mut().for_each(|decl| match &mut decl.name {
-              Pat::Ident(ident) => {
+            v.decls.iter_mut().for_each(|decl| {
+              // other patterns have already been reported in hoisting
+              if let Pat::Ident(ident) = &mut decl.name {
                 let new_mark = ident.id.span.ctxt.as_mark();
 
                 self.exports.push(ModuleExport::Name(ModuleExportName {
@@ -458,14 +915,9 @@ impl VisitMut for ModuleAnalyzer {
                   mark: new_mark,
                   src: None,
                   index: None,
+                  is_type_only: false,
                 }));
               }
-              p => {
-                log::warn!(
-                  "[ModuleAnalyzer] Pattern type {:?} should not available in dts file",
-                  p
-                )
-              }
             });
           }
           Decl::Class(c) => {
@@ -478,6 +930,7 @@ impl VisitMut for ModuleAnalyzer {
               mark: new_mark,
               src: None,
               index: None,
+              is_type_only: false,
             }))
           }
           Decl::Fn(f) => {
@@ -490,6 +943,7 @@ impl VisitMut for ModuleAnalyzer {
               mark: new_mark,
               src: None,
               index: None,
+              is_type_only: false,
             }))
           }
           Decl::TsInterface(t) => {
@@ -502,6 +956,7 @@ impl VisitMut for ModuleAnalyzer {
               mark: new_mark,
               src: None,
               index: None,
+              is_type_only: false,
             }))
           }
           Decl::TsTypeAlias(t) => {
@@ -514,6 +969,7 @@ impl VisitMut for ModuleAnalyzer {
               mark: new_mark,
               src: None,
               index: None,
+              is_type_only: false,
             }))
           }
           Decl::TsEnum(t) => {
@@ -526,9 +982,25 @@ impl VisitMut for ModuleAnalyzer {
               mark: new_mark,
               src: None,
               index: None,
+              is_type_only: false,
             }))
           }
-          Decl::TsModule(t) => {}
+          Decl::TsModule(t) => {
+            use swc_ecma_ast::TsModuleName;
+
+            t.visit_mut_with(self);
+
+            if let TsModuleName::Ident(id) = &t.id {
+              self.exports.push(ModuleExport::Name(ModuleExportName {
+                exported_name: id.sym.clone(),
+                original_ident: id.sym.clone(),
+                mark: id.span.ctxt.as_mark(),
+                src: None,
+                index: None,
+                is_type_only: false,
+              }))
+            }
+          }
         }
       }
       ModuleDecl::ExportNamed(named_export) => {
@@ -537,28 +1009,27 @@ impl VisitMut for ModuleAnalyzer {
         let ctxt = self.get_current_statement_mut().unwrap();
         ctxt.is_export = true;
 
-        let mut is_export_decl = false;
-
         named_export.specifiers.iter_mut().for_each(|s| match s {
           ExportSpecifier::Named(named) => {
-            let new_mark = self
-              .get_mark_by_name(&get_module_export_name(&named.orig))
-              // or maybe its a global variable, should we assign a new mark(only used as a placeholder) here?
-              .unwrap_or_else(|| symbol::new_mark());
+            // re-exports never reference local variables, e.g. `export { FC } from "react"` with a local `FC`
+            let new_mark = if named_export.src.is_some() {
+              symbol::new_mark()
+            } else {
+              self
+                .get_mark_by_name(&get_module_export_name(&named.orig), Meaning::ALL)
+                // or maybe its a global variable, should we assign a new mark(only used as a placeholder) here?
+                .unwrap_or_else(symbol::new_mark)
+            };
 
             let exported_name: JsWord = {
-              use swc_ecma_ast::ModuleExportName;
-
-              let name;
-              if named.exported.is_some() {
-                mark_module_export_name(named.exported.as_mut().unwrap(), new_mark);
-                name = named.exported.as_ref();
+              let name = if let Some(exported) = named.exported.as_mut() {
+                exported
               } else {
-                mark_module_export_name(&mut named.orig, new_mark);
-                name = Some(&named.orig);
-              }
+                &mut named.orig
+              };
+              mark_module_export_name(name, new_mark);
 
-              get_module_export_name(name.unwrap())
+              get_module_export_name(name)
             };
 
             let src = named_export.src.as_ref().map(|src| src.value.clone());
@@ -573,6 +1044,7 @@ impl VisitMut for ModuleAnalyzer {
               } else {
                 None
               },
+              is_type_only: named_export.type_only || named.is_type_only,
             }));
 
             if src.is_some() {
@@ -580,9 +1052,9 @@ impl VisitMut for ModuleAnalyzer {
             }
           }
           ExportSpecifier::Namespace(namespace) => {
+            // linked to the namespace of the module in `link_namespaces`
             let new_mark = symbol::new_mark();
             namespace.span.ctxt = new_mark.as_ctxt();
-            is_export_decl = true;
 
             self
               .exports
@@ -600,32 +1072,47 @@ impl VisitMut for ModuleAnalyzer {
 
             self.advance_import_index();
           }
-          ExportSpecifier::Default(default) => {}
+          ExportSpecifier::Default(_) => {}
         });
 
-        let ctxt = self.get_current_statement_mut().unwrap();
-        ctxt.is_export_decl = is_export_decl;
-
         n.visit_mut_children_with(self);
       }
       ModuleDecl::ExportDefaultDecl(export_default) => {
+        use swc_ecma_ast::{ClassExpr, DefaultDecl, FnExpr};
+
         let ctxt = self.get_current_statement_mut().unwrap();
         ctxt.is_export = true;
         ctxt.is_export_decl = true;
 
-        let name = js_word!("default");
-        let new_mark = symbol::new_mark();
+        export_default.visit_mut_children_with(self);
+
+        // declarations with identifiers have already been bound to the statement
+        let ctxt = self.get_current_statement_mut().unwrap();
+        let new_mark = *ctxt.mark.get_or_insert_with(symbol::new_mark);
         export_default.span.ctxt = new_mark.as_ctxt();
+        if ctxt.meaning.is_empty() {
+          ctxt.meaning = Meaning::ALL;
+        }
+
+        let original_ident = match &export_default.decl {
+          DefaultDecl::Class(ClassExpr {
+            ident: Some(ident), ..
+          })
+          | DefaultDecl::Fn(FnExpr {
+            ident: Some(ident), ..
+          }) => ident.sym.clone(),
+          DefaultDecl::TsInterfaceDecl(interface) => interface.id.sym.clone(),
+          _ => js_word!("default"),
+        };
 
         self.exports.push(ModuleExport::Name(ModuleExportName {
-          exported_name: name.clone(),
-          original_ident: name.clone(),
+          exported_name: js_word!("default"),
+          original_ident,
           mark: new_mark,
           src: None,
           index: None,
+          is_type_only: false,
         }));
-
-        n.visit_mut_children_with(self);
       }
       ModuleDecl::ExportAll(export_all) => {
         let ctxt = self.get_current_statement_mut().unwrap();
@@ -642,26 +1129,182 @@ impl VisitMut for ModuleAnalyzer {
 
         n.visit_mut_children_with(self);
       }
-      ModuleDecl::ExportDefaultExpr(export_default_expr) => {
-        log::warn!("[ModuleAnalyzer] `ExportDefaultExpr` should not exist in dts files");
+      ModuleDecl::ExportDefaultExpr(export_default) => {
+        use swc_ecma_ast::Expr;
+
+        let ctxt = self.get_current_statement_mut().unwrap();
+        ctxt.is_export = true;
+
+        // `export default foo` exports all meanings of `foo`, just like `export { foo as default }`
+        match &mut *export_default.expr {
+          Expr::Ident(ident) => {
+            let new_mark = self
+              .get_mark_by_name(&ident.sym, Meaning::ALL)
+              // or maybe its a global variable
+              .unwrap_or_else(symbol::new_mark);
+            ident.span.ctxt = new_mark.as_ctxt();
+
+            self.exports.push(ModuleExport::Name(ModuleExportName {
+              exported_name: js_word!("default"),
+              original_ident: ident.sym.clone(),
+              mark: new_mark,
+              src: None,
+              index: None,
+              is_type_only: false,
+            }));
+          }
+          e => {
+            log::warn!(
+              "[ModuleAnalyzer] Expression {:?} should not be exported as default in dts files",
+              e
+            )
+          }
+        }
+      }
+      ModuleDecl::TsImportEquals(import_equals) => {
+        use swc_ecma_ast::TsModuleRef;
+
+        let mark = match &import_equals.module_ref {
+          TsModuleRef::TsEntityName(_) => {
+            // the alias has already been declared in hoisting
+            self.read_ts_module_ref(&mut import_equals.module_ref);
+
+            if import_equals.is_export {
+              let ctxt = self.get_current_statement_mut().unwrap();
+              ctxt.is_export = true;
+              ctxt.is_export_decl = true;
+            }
+
+            import_equals.id.span.ctxt.as_mark()
+          }
+          TsModuleRef::TsExternalModuleRef(module_ref) => {
+            let local_name = import_equals.id.sym.clone();
+            let mark = symbol::new_mark();
+            import_equals.id.span.ctxt = mark.as_ctxt();
+
+            if self.get_top_level_names().contains(&local_name) {
+              self.error.get_or_insert(Error::new_with_reason(
+                DtsupErrorType::DuplicateDeclarationError,
+                &format!(
+                  "[ModuleAnalyzer] unable to import `{}`, it has already been declared",
+                  local_name
+                ),
+              ));
+            }
+
+            self.imports.insert(
+              local_name.clone(),
+              ModuleImport {
+                index: self.current_import_index,
+                mark,
+                local_name,
+                original_ident: ImportIdent::Name(export_equals_name()),
+                src: module_ref.expr.value.clone(),
+                is_type_only: import_equals.is_type_only,
+                is_inline: false,
+              },
+            );
+            self.advance_import_index();
+
+            let ctxt = self.get_current_statement_mut().unwrap();
+            ctxt.is_import = true;
+
+            mark
+          }
+        };
+
+        // `export import Foo = require("./foo")`
+        if import_equals.is_export {
+          self.exports.push(ModuleExport::Name(ModuleExportName {
+            exported_name: import_equals.id.sym.clone(),
+            original_ident: import_equals.id.sym.clone(),
+            mark,
+            src: None,
+            index: None,
+            is_type_only: import_equals.is_type_only,
+          }));
+        }
+      }
+      ModuleDecl::TsExportAssignment(export_assignment) => {
+        use swc_ecma_ast::Expr;
+
+        let ctxt = self.get_current_statement_mut().unwrap();
+        ctxt.is_export = true;
+
+        // `export = Foo` exports all meanings of `Foo`
+        match &mut *export_assignment.expr {
+          Expr::Ident(ident) => {
+            let new_mark = self
+              .get_mark_by_name(&ident.sym, Meaning::ALL)
+              .unwrap_or_else(symbol::new_mark);
+            ident.span.ctxt = new_mark.as_ctxt();
+
+            self.exports.push(ModuleExport::Name(ModuleExportName {
+              exported_name: export_equals_name(),
+              original_ident: ident.sym.clone(),
+              mark: new_mark,
+              src: None,
+              index: None,
+              is_type_only: false,
+            }));
+          }
+          e => {
+            log::warn!(
+              "[ModuleAnalyzer] Expression {:?} is not supported in `export =`, it is ignored",
+              e
+            )
+          }
+        }
+      }
+      ModuleDecl::TsNamespaceExport(namespace_export) => {
+        let ctxt = self.get_current_statement_mut().unwrap();
+        ctxt.is_export = true;
+
+        // codegen emits `export as namespace Foo` as `export = Foo`, which changes the exports of the bundle
+        log::warn!(
+          "[ModuleAnalyzer] `export as namespace {}` is not supported, it is dropped",
+          namespace_export.id.sym
+        );
       }
-      _ => (),
     }
   }
 
   fn visit_mut_ts_type(&mut self, n: &mut swc_ecma_ast::TsType) {
     use swc_ecma_ast::TsType;
     match n {
-      TsType::TsKeywordType(t) => {
+      TsType::TsKeywordType(_) => {
         // skip
       }
 
-      TsType::TsThisType(t) => {
+      TsType::TsThisType(_) => {
         // skip
       }
 
       TsType::TsFnOrConstructorType(t) => {
-        // TODO: is it necessary?
+        use swc_ecma_ast::TsFnOrConstructorType;
+
+        // just like functions, type parameters are available in params and the return type,
+        // e.g. `<E extends Event>(e: E) => Result`
+        match t {
+          TsFnOrConstructorType::TsFnType(t) => {
+            t.type_params.visit_mut_with(self);
+            t.params.visit_mut_with(self);
+            t.type_ann.visit_mut_with(self);
+
+            if t.type_params.is_some() {
+              self.pop_scope();
+            }
+          }
+          TsFnOrConstructorType::TsConstructorType(t) => {
+            t.type_params.visit_mut_with(self);
+            t.params.visit_mut_with(self);
+            t.type_ann.visit_mut_with(self);
+
+            if t.type_params.is_some() {
+              self.pop_scope();
+            }
+          }
+        }
       }
 
       TsType::TsTypeRef(t) => {
@@ -669,16 +1312,34 @@ impl VisitMut for ModuleAnalyzer {
         // reference to a TS type
         match &mut t.type_name {
           TsEntityName::Ident(ident) => {
-            let mut mark = self.add_variable_read(&ident.sym);
-            if let Some(mark) = mark.as_ref() {
-              ident.span.ctxt = mark.as_ctxt();
-            }
+            self.read_ident(ident, Meaning::TYPE);
+          }
+          TsEntityName::TsQualifiedName(qualified) => {
+            // `Foo` in `Foo.Bar` is a namespace or an enum, `Bar` is a member of it
+            let ident = get_leftmost_entity_ident_mut(&mut qualified.left);
+            self.read_ident(ident, Meaning::NAMESPACE);
           }
-          TsEntityName::TsQualifiedName(q) => {}
         }
+
+        t.type_params.visit_mut_with(self);
       }
 
-      TsType::TsTypeQuery(t) => {}
+      TsType::TsTypeQuery(t) => {
+        use swc_ecma_ast::TsTypeQueryExpr;
+
+        // `typeof Foo.bar` refers to the value of `Foo`
+        match &mut t.expr_name {
+          TsTypeQueryExpr::TsEntityName(name) => {
+            self.read_ident(get_leftmost_entity_ident_mut(name), Meaning::VALUE);
+          }
+          TsTypeQueryExpr::Import(import_type) => {
+            let entity_name = self.add_inline_import(import_type, Meaning::VALUE);
+            t.expr_name = TsTypeQueryExpr::TsEntityName(entity_name);
+          }
+        }
+
+        t.type_args.visit_mut_with(self);
+      }
 
       TsType::TsTypeLit(t) => {
         self.push_scope(Scope::new(ScopeKind::TypeScope));
@@ -687,35 +1348,138 @@ impl VisitMut for ModuleAnalyzer {
       }
 
       TsType::TsArrayType(t) => {
-        // recursively visit its children
-        t.elem_type.visit_mut_children_with(self);
+        t.elem_type.visit_mut_with(self);
       }
 
-      TsType::TsTupleType(t) => {}
+      TsType::TsTupleType(t) => {
+        // labels of `[first: Foo, ...rest: Bar[]]` are not variables
+        t.elem_types.iter_mut().for_each(|elem| {
+          elem.ty.visit_mut_with(self);
+        });
+      }
 
-      TsType::TsOptionalType(t) => {}
+      TsType::TsOptionalType(t) => {
+        t.type_ann.visit_mut_with(self);
+      }
 
-      TsType::TsRestType(t) => {}
+      TsType::TsRestType(t) => {
+        t.type_ann.visit_mut_with(self);
+      }
 
-      TsType::TsUnionOrIntersectionType(t) => {}
+      TsType::TsUnionOrIntersectionType(t) => {
+        use swc_ecma_ast::TsUnionOrIntersectionType;
 
-      TsType::TsConditionalType(t) => {}
+        match t {
+          TsUnionOrIntersectionType::TsUnionType(t) => t.types.visit_mut_with(self),
+          TsUnionOrIntersectionType::TsIntersectionType(t) => t.types.visit_mut_with(self),
+        }
+      }
 
-      TsType::TsInferType(t) => {}
+      TsType::TsConditionalType(t) => {
+        t.check_type.visit_mut_with(self);
 
-      TsType::TsParenthesizedType(t) => {}
+        // `infer U` in `extends` clause is only available in the true branch,
+        // e.g. `T extends Foo<infer U> ? Bar<U> : Baz`
+        self.push_scope(Scope::new(ScopeKind::TsConditionalType));
+        t.extends_type.visit_mut_with(self);
+        t.true_type.visit_mut_with(self);
+        self.pop_scope();
 
-      TsType::TsTypeOperator(t) => {}
+        t.false_type.visit_mut_with(self);
+      }
 
-      TsType::TsIndexedAccessType(t) => {}
+      TsType::TsInferType(t) => {
+        self.declare_infer_type(&mut t.type_param);
+      }
 
-      TsType::TsMappedType(t) => {}
+      TsType::TsParenthesizedType(t) => {
+        t.type_ann.visit_mut_with(self);
+      }
 
-      TsType::TsLitType(t) => {}
+      TsType::TsTypeOperator(t) => {
+        // `keyof Foo`, `unique symbol` or `readonly Foo[]`
+        t.type_ann.visit_mut_with(self);
+      }
 
-      TsType::TsTypePredicate(t) => {}
+      TsType::TsIndexedAccessType(t) => {
+        // `Foo[K]`
+        t.obj_type.visit_mut_with(self);
+        t.index_type.visit_mut_with(self);
+      }
 
-      TsType::TsImportType(t) => {}
+      TsType::TsMappedType(t) => {
+        use swc_common::Spanned;
+        use swc_ecma_ast::TruePlusMinus;
+
+        // codegen drops `as` clauses and emits `+?` as `+/`, which changes the meaning of mapped types
+        let unsupported = match (&t.name_type, t.optional) {
+          (Some(name_type), _) => Some(("`as` clauses", name_type.span())),
+          (None, Some(TruePlusMinus::Plus)) => Some(("`+?` modifiers", t.span)),
+          _ => None,
+        };
+        if let Some((syntax, span)) = unsupported {
+          let loc = SOURCE_MAP.lookup_char_pos(span.lo);
+          self.error.get_or_insert(Error::new_with_reason(
+            DtsupErrorType::UnsupportedSyntaxError,
+            &format!(
+              "[ModuleAnalyzer] {} of mapped types are not supported at {}:{}",
+              syntax,
+              loc.line,
+              loc.col_display + 1
+            ),
+          ));
+        }
+
+        // `K` is not available in its constraint
+        t.type_param.constraint.visit_mut_with(self);
+
+        // `{ [K in keyof Foo as Rename<K>]: Bar[K] }`, `K` is available in the `as` clause and the value type
+        self.push_scope(Scope::new(ScopeKind::TypeScope));
+        let new_mark = self.add_variable_definition(
+          t.type_param.name.sym.clone(),
+          VariableDeclaration::TsTypeParameter,
+          symbol::new_mark(),
+        );
+        t.type_param.name.span.ctxt = new_mark.as_ctxt();
+
+        t.name_type.visit_mut_with(self);
+        t.type_ann.visit_mut_with(self);
+        self.pop_scope();
+      }
+
+      TsType::TsLitType(t) => {
+        use swc_ecma_ast::TsLit;
+
+        // types in template literal types, e.g. `get${Capitalize<K>}`
+        if let TsLit::Tpl(tpl) = &mut t.lit {
+          tpl.types.visit_mut_with(self);
+        }
+      }
+
+      TsType::TsTypePredicate(t) => {
+        // `asserts x is T`, `x is T` or `this is T`, `x` is a parameter, only `T` should be read
+        t.type_ann.visit_mut_with(self);
+      }
+
+      TsType::TsImportType(t) => {
+        use swc_ecma_ast::{TsEntityName, TsTypeRef};
+
+        t.type_args.visit_mut_with(self);
+
+        // `import("./foo").Bar` is a type, while `import("./foo").Bar.Baz` reads namespace `Bar`
+        let meaning = if matches!(t.qualifier, Some(TsEntityName::Ident(_))) {
+          Meaning::TYPE
+        } else {
+          Meaning::NAMESPACE
+        };
+
+        let type_name = self.add_inline_import(t, meaning);
+        *n = TsType::TsTypeRef(TsTypeRef {
+          span: t.span,
+          type_name,
+          type_params: t.type_args.take(),
+        });
+      }
     }
   }
 }
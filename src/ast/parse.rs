@@ -7,7 +7,7 @@ use smol_str::SmolStr;
 use tokio::fs::File;
 
 use swc::{config::IsModule, Compiler};
-use swc_common::{errors, FileName, FilePathMapping, SourceMap};
+use swc_common::{FileName, FilePathMapping, SourceMap};
 use swc_ecma_parser::Syntax;
 use tokio::io::AsyncReadExt;
 
@@ -24,56 +24,80 @@ pub(crate) static COMPILER: Lazy<Arc<Compiler>> = Lazy::new(|| {
 pub async fn parse_file(file_name: SmolStr) -> Result<swc_ecma_ast::Module, Error> {
   let compiler = COMPILER.clone();
 
-  let is_dts = file_name.ends_with(".d.ts");
+  let is_dts = [".d.ts", ".d.mts", ".d.cts"]
+    .iter()
+    .any(|ext| file_name.ends_with(ext));
+  let is_tsx = file_name.ends_with(".tsx");
 
-  if is_dts {
-    let path = Path::new(file_name.as_str());
-    let mut file = File::open(path).await.unwrap();
-    debug!(
-      "[Parse]: parsing file {}",
-      path.to_string_lossy().to_string()
-    );
-    let mut buf: Vec<u8> = Default::default();
-    file.read_to_end(&mut buf).await?;
-
-    let fm = compiler.cm.new_source_file(
-      FileName::Custom(file_name.into()),
-      String::from_utf8(buf).unwrap_or_default(),
-    );
-
-    let syntax = Syntax::Typescript(swc_ecma_parser::TsConfig {
-      dts: true,
-      ..Default::default()
-    });
-
-    swc::try_with_handler(
-      Arc::new(SourceMap::new(FilePathMapping::empty())),
-      Default::default(),
-      |handler| {
-        compiler.parse_js(
-          fm,
-          handler,
-          swc_ecma_ast::EsVersion::Es2022,
-          syntax,
-          IsModule::Bool(true),
-          None,
-        )
-      },
-    )
-    .and_then(|program| Ok(program.expect_module()))
-    .or_else(|error| {
-      Err(Error::new_with_reason(
-        DtsupErrorType::ParseFileError,
-        error.to_string().as_str(),
-      ))
-    })
-  } else {
-    Err(Error::new_with_reason(
+  if ![".ts", ".mts", ".cts"]
+    .iter()
+    .any(|ext| file_name.ends_with(ext))
+    && !is_tsx
+  {
+    return Err(Error::new_with_reason(
       DtsupErrorType::ParseFileError,
       &format!(
-        "Unable to parse non TypeScript declaration file {}",
+        "Unable to parse non TypeScript file {}, only `.d.ts`, `.d.mts`, `.d.cts`, `.ts`, `.mts`, `.cts` and `.tsx` files are supported",
         file_name,
       ),
-    ))
+    ));
+  }
+
+  let path = Path::new(file_name.as_str());
+  let mut file = File::open(path).await.map_err(|error| {
+    Error::new_with_reason(
+      DtsupErrorType::IOError,
+      &format!("[Parse] unable to open {}: {}", file_name, error),
+    )
+  })?;
+  debug!(
+    "[Parse]: parsing file {}",
+    path.to_string_lossy().to_string()
+  );
+  let mut buf: Vec<u8> = Default::default();
+  file.read_to_end(&mut buf).await?;
+
+  let source = String::from_utf8(buf).map_err(|error| {
+    Error::new_with_reason(
+      DtsupErrorType::UTF8Error,
+      &format!("[Parse] {} is not valid UTF-8: {}", file_name, error),
+    )
+  })?;
+
+  let fm = compiler
+    .cm
+    .new_source_file(FileName::Custom(file_name.to_string()), source);
+
+  let syntax = Syntax::Typescript(swc_ecma_parser::TsConfig {
+    tsx: is_tsx,
+    decorators: !is_dts,
+    dts: is_dts,
+    ..Default::default()
+  });
+
+  let swc_module = swc::try_with_handler(
+    Arc::new(SourceMap::new(FilePathMapping::empty())),
+    Default::default(),
+    |handler| {
+      compiler.parse_js(
+        fm,
+        handler,
+        swc_ecma_ast::EsVersion::Es2022,
+        syntax,
+        IsModule::Bool(true),
+        None,
+      )
+    },
+  )
+  .map(|program| program.expect_module())
+  .map_err(|error| {
+    Error::new_with_reason(DtsupErrorType::ParseFileError, error.to_string().as_str())
+  })?;
+
+  // sources are bundled with declarations generated from them
+  if is_dts {
+    Ok(swc_module)
+  } else {
+    super::declaration::generate_declarations(&file_name, swc_module)
   }
 }
@@ -1,24 +1,130 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::HashMap;
+use std::ops::{BitOr, BitOrAssign};
 
 use swc_atoms::JsWord;
 use swc_common::Mark;
 
-use super::symbol;
+use crate::result::{DtsupErrorType, Error};
 
-#[derive(Debug)]
+/// Meaning spaces of a symbol, names in different meaning spaces do not conflict with each other,
+/// e.g. `interface Foo {}` and `declare const Foo: FooConstructor` are both available in the same scope.
+/// A declaration may introduce multiple meanings, classes are both types and values.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Meaning(u8);
+
+impl Meaning {
+  pub const TYPE: Meaning = Meaning(1);
+  pub const VALUE: Meaning = Meaning(1 << 1);
+  pub const NAMESPACE: Meaning = Meaning(1 << 2);
+  pub const ALL: Meaning = Meaning(Self::TYPE.0 | Self::VALUE.0 | Self::NAMESPACE.0);
+
+  #[inline]
+  pub fn is_empty(self) -> bool {
+    self.0 == 0
+  }
+
+  #[inline]
+  pub fn contains(self, other: Meaning) -> bool {
+    self.0 & other.0 == other.0
+  }
+
+  #[inline]
+  pub fn intersects(self, other: Meaning) -> bool {
+    self.0 & other.0 != 0
+  }
+}
+
+impl BitOr for Meaning {
+  type Output = Meaning;
+
+  fn bitor(self, rhs: Meaning) -> Meaning {
+    Meaning(self.0 | rhs.0)
+  }
+}
+
+impl BitOrAssign for Meaning {
+  fn bitor_assign(&mut self, rhs: Meaning) {
+    self.0 |= rhs.0
+  }
+}
+
+#[derive(Debug, Clone)]
+#[allow(clippy::enum_variant_names)]
 pub enum VariableDeclaration {
   TsInterfaceDeclaration,
   TsTypeAliasDeclaration,
   TsTypeParameter,
   TsEnumDeclaration,
+  /// Namespaces which only contain types are not instantiated, they do not declare values
+  TsNamespaceDeclaration {
+    is_instantiated: bool,
+  },
+  /// `import Bar = Foo.Bar` aliases all meanings of the entity
+  TsImportEqualsDeclaration,
   VariableDeclaration,
   FunctionDeclaration,
   ClassDeclaration,
 }
 
+impl VariableDeclaration {
+  /// Meanings introduced by the declaration
+  pub fn meaning(&self) -> Meaning {
+    match self {
+      Self::TsInterfaceDeclaration | Self::TsTypeAliasDeclaration | Self::TsTypeParameter => {
+        Meaning::TYPE
+      }
+      // enum members are accessible with `Enum.Member` in types
+      Self::TsEnumDeclaration => Meaning::ALL,
+      Self::TsImportEqualsDeclaration => Meaning::ALL,
+      // `typeof ns` or `ns.foo` refers to the object of instantiated namespaces
+      Self::TsNamespaceDeclaration {
+        is_instantiated: true,
+      } => Meaning::NAMESPACE | Meaning::VALUE,
+      Self::TsNamespaceDeclaration {
+        is_instantiated: false,
+      } => Meaning::NAMESPACE,
+      Self::VariableDeclaration | Self::FunctionDeclaration => Meaning::VALUE,
+      Self::ClassDeclaration => Meaning::TYPE | Meaning::VALUE,
+    }
+  }
+
+  /// Declarations which are able to be declared multiple times with the same name in the same scope,
+  /// these declarations share the same symbol.
+  /// See: https://www.typescriptlang.org/docs/handbook/declaration-merging.html
+  pub fn is_mergeable_with(&self, other: &VariableDeclaration) -> bool {
+    use VariableDeclaration::{
+      ClassDeclaration, FunctionDeclaration, TsEnumDeclaration, TsInterfaceDeclaration,
+      TsNamespaceDeclaration,
+    };
+
+    matches!(
+      (self, other),
+      // interfaces and enums are merged, and functions are overloaded
+      (TsInterfaceDeclaration, TsInterfaceDeclaration)
+        | (FunctionDeclaration, FunctionDeclaration)
+        | (TsEnumDeclaration, TsEnumDeclaration)
+        // namespaces are merged with namespaces, variables, functions, classes and enums
+        | (TsNamespaceDeclaration { .. }, TsNamespaceDeclaration { .. })
+        | (
+          TsNamespaceDeclaration { .. },
+          Self::VariableDeclaration | FunctionDeclaration | ClassDeclaration | TsEnumDeclaration
+        )
+        | (
+          Self::VariableDeclaration | FunctionDeclaration | ClassDeclaration | TsEnumDeclaration,
+          TsNamespaceDeclaration { .. }
+        )
+        // interfaces are merged with classes
+        | (TsInterfaceDeclaration, ClassDeclaration)
+        | (ClassDeclaration, TsInterfaceDeclaration)
+    )
+  }
+}
+
 #[derive(Debug)]
 pub enum ScopeKind {
   TsTypeParameter,
+  // `infer` declarations of a conditional type, which are only available in the true branch
+  TsConditionalType,
   TypeScope,
 }
 
@@ -35,14 +141,17 @@ impl Definition {
 }
 
 /// Good to notice:
-/// Type and value declarations are actually two totally different stuff, but in this scenario, single scope type makes sense.
+/// Types, values and namespaces are declared in separated tables, since they are in different meaning spaces.
+/// All meanings of the same name in a single scope share the same mark,
+/// statements are included by their mark and the meanings they declare.
 /// In JavaScript, we should differentiate scopes into function scope, block scope, etc.
 #[derive(Debug)]
 pub struct Scope {
   pub(crate) kind: ScopeKind,
-  pub(crate) definitions: HashMap<JsWord, Definition>,
-  mark_to_name: HashMap<Mark, JsWord>,
-  reads: HashSet<Mark>,
+  types: HashMap<JsWord, Definition>,
+  values: HashMap<JsWord, Definition>,
+  namespaces: HashMap<JsWord, Definition>,
+  reads: HashMap<Mark, Meaning>,
 }
 
 impl Default for Scope {
@@ -55,52 +164,85 @@ impl Scope {
   pub fn new(kind: ScopeKind) -> Self {
     Self {
       kind,
-      definitions: Default::default(),
-      mark_to_name: Default::default(),
+      types: Default::default(),
+      values: Default::default(),
+      namespaces: Default::default(),
       reads: Default::default(),
     }
   }
 
-  pub fn get_scope_kind(&self) -> &ScopeKind {
-    &self.kind
+  fn tables(&self) -> [(Meaning, &HashMap<JsWord, Definition>); 3] {
+    [
+      (Meaning::TYPE, &self.types),
+      (Meaning::VALUE, &self.values),
+      (Meaning::NAMESPACE, &self.namespaces),
+    ]
+  }
+
+  /// Names declared in current scope with any meaning
+  pub fn get_names(&self) -> impl Iterator<Item = &JsWord> {
+    self
+      .types
+      .keys()
+      .chain(self.values.keys())
+      .chain(self.namespaces.keys())
   }
 
-  pub fn get_variable_definition(&self, name: &JsWord) -> Option<&Definition> {
-    self.definitions.get(name)
+  /// Returns the definition of `name` in any of the meaning spaces of `meaning`
+  pub fn get_variable_definition(&self, name: &JsWord, meaning: Meaning) -> Option<&Definition> {
+    self
+      .tables()
+      .into_iter()
+      .filter(|(m, _)| meaning.intersects(*m))
+      .find_map(|(_, table)| table.get(name))
   }
 
+  /// Returns the mark of the declared variable, which is the mark of the name in current scope
+  /// if the name has already been declared in any meaning space, or `mark` otherwise.
   pub fn add_variable_definition(
     &mut self,
     name: JsWord,
     definition_type: VariableDeclaration,
     mark: Mark,
-  ) {
-    use std::collections::hash_map::Entry;
+  ) -> Result<Mark, Error> {
+    let meaning = definition_type.meaning();
 
-    match self.definitions.entry(name.clone()) {
-      Entry::Vacant(vacant) => {
-        vacant.insert(Definition::new(mark.clone(), definition_type));
-        self.mark_to_name.insert(mark, name);
-      }
-      Entry::Occupied(o) => {
-        // Interfaces may be defined multiple times in the same scope
-        if !matches!(definition_type, VariableDeclaration::TsInterfaceDeclaration)
-          || !matches!(o.get().kind, VariableDeclaration::TsInterfaceDeclaration)
-        {
-          panic!(
-            "[Scope] unable to declare {:?} multiple times",
-            definition_type
-          )
+    for (m, table) in self.tables() {
+      if let Some(def) = table.get(&name) {
+        // Interfaces and function overloads may be defined multiple times in the same scope
+        if meaning.intersects(m) && !def.kind.is_mergeable_with(&definition_type) {
+          return Err(Error::new_with_reason(
+            DtsupErrorType::DuplicateDeclarationError,
+            &format!(
+              "[Scope] unable to declare `{}` as {:?}, it has already been declared as {:?}",
+              name, definition_type, def.kind
+            ),
+          ));
         }
       }
     }
-  }
 
-  pub fn add_variable_read(&mut self, variable_mark: Mark) {
-    self.reads.insert(variable_mark);
+    let mark = self
+      .get_variable_definition(&name, Meaning::ALL)
+      .map_or(mark, |def| def.mark);
+
+    [
+      (Meaning::TYPE, &mut self.types),
+      (Meaning::VALUE, &mut self.values),
+      (Meaning::NAMESPACE, &mut self.namespaces),
+    ]
+    .into_iter()
+    .filter(|(m, _)| meaning.intersects(*m))
+    .for_each(|(_, table)| {
+      table
+        .entry(name.clone())
+        .or_insert_with(|| Definition::new(mark, definition_type.clone()));
+    });
+
+    Ok(mark)
   }
 
-  pub fn get_reads(&self) -> &HashSet<Mark> {
-    &self.reads
+  pub fn add_variable_read(&mut self, variable_mark: Mark, meaning: Meaning) {
+    *self.reads.entry(variable_mark).or_default() |= meaning;
   }
 }
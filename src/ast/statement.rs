@@ -1,17 +1,23 @@
-use std::collections::HashSet;
+use std::collections::HashMap;
 
 use swc_common::Mark;
-use swc_ecma_ast::{ExportSpecifier, ModuleDecl, ModuleItem, NamedExport};
+use swc_ecma_ast::{ModuleDecl, ModuleItem};
 
+use super::scope::Meaning;
+
+// Import statements and non-declarative export statements are kept as is, they are not generated for now
 #[derive(Debug)]
 pub enum Statement {
   DeclStatement(DeclStatement),
+  #[allow(dead_code)]
   ImportStatement(ImportStatement),
+  #[allow(dead_code)]
   ExportStatementNonDecl(ExportStatementNonDecl),
 }
 
 #[derive(Debug)]
 pub struct ImportStatement {
+  #[allow(dead_code)]
   pub node: ModuleItem,
 }
 
@@ -23,6 +29,7 @@ impl ImportStatement {
 
 #[derive(Debug)]
 pub struct ExportStatementNonDecl {
+  #[allow(dead_code)]
   pub node: ModuleItem,
 }
 
@@ -36,14 +43,18 @@ impl ExportStatementNonDecl {
 pub struct DeclStatement {
   pub node: ModuleItem,
   pub included: bool,
-  pub reads: HashSet<Mark>,
-  // This includes export named declarations / export default declarations / export namespaced declarations,
+  pub reads: HashMap<Mark, Meaning>,
+  // This includes export named declarations / export default declarations / `export import Foo = Bar.Foo`,
   // since these should be transformed
   pub is_export_decl: bool,
+  // global augmentations and ambient module declarations are always included
+  pub has_side_effects: bool,
 
   // `tree-shaking` is supported by including this mark
   // `mark` equals to the mark of node's declaration's ident
   pub mark: Mark,
+  // meanings declared by the statement, only references to these meanings will include the statement
+  pub meaning: Meaning,
 }
 
 impl DeclStatement {
@@ -52,8 +63,10 @@ impl DeclStatement {
       node,
       included: false,
       is_export_decl: Default::default(),
+      has_side_effects: Default::default(),
       reads: Default::default(),
       mark: Default::default(),
+      meaning: Default::default(),
     }
   }
 
@@ -76,19 +89,6 @@ impl DeclStatement {
         self.node, self.is_export_decl
       )
     }
-
-    if let ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(export_named)) = &self.node {
-      assert_eq!(
-        matches!(
-          export_named.specifiers.get(0),
-          Some(ExportSpecifier::Namespace(_))
-        ),
-        self.is_export_decl,
-        "[Statement]: failed to validate node type of {:#?} with `is_export_decl` {}",
-        self.node,
-        self.is_export_decl
-      )
-    }
   }
 
   pub fn include(&mut self) {
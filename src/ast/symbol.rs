@@ -49,10 +49,6 @@ impl SymbolBox {
     self.mark_uf.union(a, b)
   }
 
-  pub fn unioned(&mut self, a: Mark, b: Mark) -> bool {
-    self.mark_uf.unioned(a, b)
-  }
-
   pub fn find_root(&mut self, a: Mark) -> Mark {
     self.mark_uf.find(a).into()
   }
@@ -1,23 +1,188 @@
 use swc_atoms::JsWord;
 use swc_common::Mark;
-use swc_ecma_ast::ModuleExportName;
+use swc_ecma_ast::{
+  Decl, DefaultDecl, ExportDecl, Expr, Ident, ModuleDecl, ModuleExportName, ModuleItem, Stmt,
+  TsEntityName, TsModuleName, TsNamespaceBody,
+};
 
 use super::symbol::MarkExt;
 
+/// Names of imports and exports may be string literals, e.g. `export { foo as "foo-bar" }`
+/// See: https://tc39.es/ecma262/#prod-ModuleExportName
 pub fn get_module_export_name(name: &ModuleExportName) -> JsWord {
   match name {
     ModuleExportName::Ident(ident) => ident.sym.clone(),
-    ModuleExportName::Str(_) => {
-      panic!("failed")
-    }
+    ModuleExportName::Str(s) => s.value.clone(),
   }
 }
 
+/// `export = Foo` is represented as an export named `export=`, which is never a valid export name,
+/// and `import Foo = require("./foo")` imports it
+pub fn export_equals_name() -> JsWord {
+  JsWord::from("export=")
+}
+
 pub fn mark_module_export_name(name: &mut ModuleExportName, mark: Mark) {
   match name {
     ModuleExportName::Ident(ident) => {
       ident.span.ctxt = mark.as_ctxt();
     }
-    ModuleExportName::Str(_) => {}
+    ModuleExportName::Str(s) => {
+      s.span.ctxt = mark.as_ctxt();
+    }
+  }
+}
+
+/// Reserved words are valid in export names, e.g. `export { foo as default }`,
+/// other names are only able to be represented as string literals
+pub fn is_valid_module_export_ident(name: &str) -> bool {
+  let mut chars = name.chars();
+
+  chars.next().is_some_and(Ident::is_valid_start) && chars.all(Ident::is_valid_continue)
+}
+
+/// Generate an identifier name for the namespace of a module, e.g. `foo_bar` for `@scope/foo-bar`
+pub fn get_namespace_ident_name(src: &str) -> JsWord {
+  let name = src
+    .rsplit('/')
+    .next()
+    .unwrap_or_default()
+    .chars()
+    .map(|c| if Ident::is_valid_continue(c) { c } else { '_' })
+    .collect::<String>();
+
+  if name.starts_with(Ident::is_valid_start) {
+    name.into()
+  } else {
+    format!("_{}", name).into()
+  }
+}
+
+/// Get the leftmost identifier of an entity name like expression, e.g. `Foo` in `Foo.Bar.Baz`
+pub fn get_leftmost_ident_mut(expr: &mut Expr) -> Option<&mut Ident> {
+  match expr {
+    Expr::Ident(ident) => Some(ident),
+    Expr::Member(member) => get_leftmost_ident_mut(&mut member.obj),
+    _ => None,
+  }
+}
+
+/// Get the leftmost identifier of an entity name, e.g. `Foo` in `Foo.Bar.Baz`
+pub fn get_leftmost_entity_ident_mut(name: &mut TsEntityName) -> &mut Ident {
+  match name {
+    TsEntityName::Ident(ident) => ident,
+    TsEntityName::TsQualifiedName(qualified) => get_leftmost_entity_ident_mut(&mut qualified.left),
+  }
+}
+
+/// Split variable declarations with multiple declarators into single declarator statements,
+/// e.g. `export declare const a: A, b: B` -> `export declare const a: A; export declare const b: B`,
+/// so that each variable is able to be tree-shaken by its own statement.
+pub fn split_var_decls(body: Vec<ModuleItem>) -> Vec<ModuleItem> {
+  body
+    .into_iter()
+    .flat_map(|module_item| match module_item {
+      ModuleItem::Stmt(Stmt::Decl(Decl::Var(var_decl))) if var_decl.decls.len() > 1 => var_decl
+        .decls
+        .iter()
+        .cloned()
+        .map(|decl| {
+          ModuleItem::Stmt(Stmt::Decl(Decl::Var(swc_ecma_ast::VarDecl {
+            decls: vec![decl],
+            ..var_decl.clone()
+          })))
+        })
+        .collect::<Vec<_>>(),
+      ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+        span,
+        decl: Decl::Var(var_decl),
+      }))
+        if var_decl.decls.len() > 1 =>
+      {
+        var_decl
+          .decls
+          .iter()
+          .cloned()
+          .map(|decl| {
+            ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+              span,
+              decl: Decl::Var(swc_ecma_ast::VarDecl {
+                decls: vec![decl],
+                ..var_decl.clone()
+              }),
+            }))
+          })
+          .collect::<Vec<_>>()
+      }
+      module_item => vec![module_item],
+    })
+    .collect()
+}
+
+/// Namespaces are instantiated if they contain values, e.g. `namespace Foo { const bar: string }`,
+/// namespaces which only contain types do not declare values.
+/// See: https://www.typescriptlang.org/docs/handbook/namespaces.html#non-instantiated-namespaces
+pub fn is_instantiated_namespace(body: Option<&TsNamespaceBody>) -> bool {
+  let is_instantiated_decl = |decl: &Decl| match decl {
+    Decl::Class(_) | Decl::Fn(_) | Decl::Var(_) | Decl::TsEnum(_) => true,
+    Decl::TsModule(ts_module) => is_instantiated_namespace(ts_module.body.as_ref()),
+    Decl::TsInterface(_) | Decl::TsTypeAlias(_) => false,
+  };
+
+  match body {
+    Some(TsNamespaceBody::TsModuleBlock(block)) => block.body.iter().any(|item| match item {
+      ModuleItem::Stmt(Stmt::Decl(decl))
+      | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. })) => {
+        is_instantiated_decl(decl)
+      }
+      ModuleItem::Stmt(_) => false,
+      // aliases and re-exports may refer to values
+      ModuleItem::ModuleDecl(_) => true,
+    }),
+    Some(TsNamespaceBody::TsNamespaceDecl(namespace_decl)) => {
+      is_instantiated_namespace(Some(&namespace_decl.body))
+    }
+    None => false,
+  }
+}
+
+/// Get the identifier declared by a top-level statement, e.g. `Foo` in `export declare class Foo {}`,
+/// ambient modules like `declare module "foo" {}` and `declare global {}` do not declare identifiers.
+pub fn get_declaration_ident(module_item: &ModuleItem) -> Option<&Ident> {
+  fn get_decl_ident(decl: &Decl) -> Option<&Ident> {
+    match decl {
+      Decl::Class(class_decl) => Some(&class_decl.ident),
+      Decl::Fn(fn_decl) => Some(&fn_decl.ident),
+      Decl::Var(var_decl) => var_decl
+        .decls
+        .first()
+        .and_then(|decl| decl.name.as_ident())
+        .map(|ident| &ident.id),
+      Decl::TsInterface(interface_decl) => Some(&interface_decl.id),
+      Decl::TsTypeAlias(type_alias_decl) => Some(&type_alias_decl.id),
+      Decl::TsEnum(enum_decl) => Some(&enum_decl.id),
+      Decl::TsModule(module_decl) => match &module_decl.id {
+        TsModuleName::Ident(ident) if !module_decl.global => Some(ident),
+        _ => None,
+      },
+    }
+  }
+
+  match module_item {
+    ModuleItem::Stmt(Stmt::Decl(decl))
+    | ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl { decl, .. })) => {
+      get_decl_ident(decl)
+    }
+    ModuleItem::ModuleDecl(ModuleDecl::ExportDefaultDecl(export_default_decl)) => {
+      match &export_default_decl.decl {
+        DefaultDecl::Class(class_expr) => class_expr.ident.as_ref(),
+        DefaultDecl::Fn(fn_expr) => fn_expr.ident.as_ref(),
+        DefaultDecl::TsInterfaceDecl(interface_decl) => Some(&interface_decl.id),
+      }
+    }
+    ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(import_equals_decl)) => {
+      Some(&import_equals_decl.id)
+    }
+    _ => None,
   }
 }
@@ -2,11 +2,16 @@ use std::collections::{HashMap, HashSet};
 
 use smol_str::SmolStr;
 
+use swc_common::Mark;
+
 use crate::ast::parse::SOURCE_MAP;
 use crate::ast::{
   module::{Exports, LocalName},
+  scope::Meaning,
   statement::{DeclStatement, Statement},
+  symbol,
 };
+use crate::externals::UnresolvedImportPolicy;
 use crate::finalizer::Finalizer;
 use crate::graph::{Graph, GraphOptions, ModuleIndex};
 use crate::result::{DtsupErrorType, Error};
@@ -15,11 +20,26 @@ type TopLevelExports = HashMap<LocalName, Exports>;
 
 pub struct Dtsup {
   entry: SmolStr,
+  externals: Vec<SmolStr>,
+  auto_externals: bool,
+  inline_packages: Vec<SmolStr>,
+  unresolved_import: UnresolvedImportPolicy,
   graph: Option<Box<Graph>>,
 }
 
 pub struct DtsupOptions<T: AsRef<str>> {
   pub entry: T,
+  /// Modules which are imported in the output instead of being bundled, subpaths are included,
+  /// e.g. `react/jsx-runtime` is external with `react`
+  pub externals: Vec<T>,
+  /// Treat `dependencies`, `peerDependencies` and `optionalDependencies` of the nearest `package.json`,
+  /// along with Node.js builtins, as externals
+  pub auto_externals: bool,
+  /// Packages whose declarations are resolved from `node_modules` and bundled instead of being imported,
+  /// dependencies of inlined packages are external
+  pub inline_packages: Vec<T>,
+  /// Fail, warn and treat as external, or decide with a callback for imports which are not able to be resolved
+  pub unresolved_import: UnresolvedImportPolicy,
 }
 
 pub struct DtsupGenerateOptions<T: AsRef<str>> {
@@ -37,11 +57,19 @@ impl Dtsup {
   {
     Self {
       entry: options.entry.into(),
+      externals: options.externals.into_iter().map(Into::into).collect(),
+      auto_externals: options.auto_externals,
+      inline_packages: options
+        .inline_packages
+        .into_iter()
+        .map(Into::into)
+        .collect(),
+      unresolved_import: options.unresolved_import,
       graph: Default::default(),
     }
   }
 
-  fn generate_build_result(&self, graph: &Box<Graph>) -> (Vec<DeclStatement>, TopLevelExports) {
+  fn generate_build_result(&self, graph: &Graph) -> (Vec<DeclStatement>, TopLevelExports) {
     let mut visited: HashSet<ModuleIndex> = Default::default();
 
     let decl_statement = graph
@@ -82,6 +110,10 @@ impl Dtsup {
   pub async fn build(&mut self) -> Result<(), Error> {
     let mut graph = Box::new(Graph::new(GraphOptions {
       entry: self.entry.clone(),
+      externals: self.externals.clone(),
+      auto_externals: self.auto_externals,
+      inline_packages: self.inline_packages.clone(),
+      unresolved_import: self.unresolved_import.clone(),
     }));
 
     graph.build().await?;
@@ -91,22 +123,41 @@ impl Dtsup {
     Ok(())
   }
 
-  fn generate_with_graph(&self, graph: &Box<Graph>) -> Result<GenerateResult, Error> {
+  fn generate_with_graph(&self, graph: &Graph) -> Result<GenerateResult, Error> {
     use swc_ecma_ast::{EsVersion, ModuleItem};
     use swc_ecma_codegen::text_writer::JsWriter;
     use swc_ecma_visit::FoldWith;
 
-    let (mut decl_statement, top_level_exports) = self.generate_build_result(graph);
+    let (decl_statement, top_level_exports) = self.generate_build_result(graph);
+
+    let mut declared_meanings: HashMap<Mark, Meaning> = Default::default();
+    decl_statement.iter().for_each(|s| {
+      let repr_mark = symbol::SYMBOL_BOX.lock().find_root(s.mark);
+      *declared_meanings.entry(repr_mark).or_default() |= s.meaning;
+    });
 
-    let mut finalizer = Finalizer { top_level_exports };
+    let mut finalizer = Finalizer {
+      top_level_exports,
+      declared_meanings,
+      external_imports: graph.get_external_imports().clone(),
+      external_star_exports: graph.get_external_star_exports().clone(),
+      module_namespaces: graph.get_module_namespaces().clone(),
+      binding_names: Default::default(),
+      referenced_externals: Default::default(),
+    };
 
-    let mut folded_statements = decl_statement
+    let mut statements = decl_statement
       .into_iter()
-      .map(|s| s.node.fold_with(&mut finalizer))
+      .map(|s| s.node)
       .collect::<Vec<ModuleItem>>();
+    finalizer.deconflict_bindings(&statements);
+    finalizer.rename_references(&mut statements);
+    finalizer.expand_qualified_namespaces(&mut statements);
 
-    let top_level_export_statement = finalizer.generate_exports();
-    folded_statements.push(top_level_export_statement);
+    let mut folded_statements = finalizer.generate_imports();
+    folded_statements.extend(statements.into_iter().map(|s| s.fold_with(&mut finalizer)));
+    folded_statements.extend(finalizer.generate_namespaces());
+    folded_statements.extend(finalizer.generate_exports());
 
     let mut output = Default::default();
 
@@ -124,13 +175,13 @@ impl Dtsup {
     };
 
     folded_statements.iter().for_each(|s| {
-      emitter.emit_module_item(s).unwrap();
+      Finalizer::emit_module_item(&mut emitter, s).unwrap();
     });
 
     Ok(GenerateResult { code: output })
   }
 
-  pub fn generate<T>(&self, options: DtsupGenerateOptions<T>) -> Result<GenerateResult, Error>
+  pub fn generate<T>(&self, _options: DtsupGenerateOptions<T>) -> Result<GenerateResult, Error>
   where
     T: AsRef<str>,
   {
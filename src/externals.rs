@@ -0,0 +1,323 @@
+use std::fmt::{Debug, Formatter};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use smol_str::SmolStr;
+
+use crate::result::{DtsupErrorType, Error};
+use crate::utils::{is_bare_import, resolve_package_entry};
+
+/// Builtin modules of Node.js, modules prefixed with `node:` are always builtin
+/// See: https://nodejs.org/api/modules.html#core-modules
+const NODE_BUILTINS: &[&str] = &[
+  "assert",
+  "assert/strict",
+  "async_hooks",
+  "buffer",
+  "child_process",
+  "cluster",
+  "console",
+  "constants",
+  "crypto",
+  "dgram",
+  "diagnostics_channel",
+  "dns",
+  "dns/promises",
+  "domain",
+  "events",
+  "fs",
+  "fs/promises",
+  "http",
+  "http2",
+  "https",
+  "inspector",
+  "module",
+  "net",
+  "os",
+  "path",
+  "path/posix",
+  "path/win32",
+  "perf_hooks",
+  "process",
+  "punycode",
+  "querystring",
+  "readline",
+  "readline/promises",
+  "repl",
+  "stream",
+  "stream/consumers",
+  "stream/promises",
+  "stream/web",
+  "string_decoder",
+  "sys",
+  "timers",
+  "timers/promises",
+  "tls",
+  "trace_events",
+  "tty",
+  "url",
+  "util",
+  "util/types",
+  "v8",
+  "vm",
+  "wasi",
+  "worker_threads",
+  "zlib",
+];
+
+/// Action for an import which is not able to be resolved
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UnresolvedImportAction {
+  Error,
+  /// Keep the import as is
+  External,
+}
+
+/// Callback receiving the importer and the specifier of an unresolved import
+pub type UnresolvedImportCallback = dyn Fn(&str, &str) -> UnresolvedImportAction + Send + Sync;
+
+/// Policy of imports which are not able to be resolved to declaration files
+#[derive(Clone, Default)]
+pub enum UnresolvedImportPolicy {
+  /// Fail the build with the importer and the specifier
+  #[default]
+  Error,
+  /// Warn and treat the import as external
+  Warn,
+  /// Decide with a callback receiving the importer and the specifier
+  Custom(Arc<UnresolvedImportCallback>),
+}
+
+impl Debug for UnresolvedImportPolicy {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    match self {
+      Self::Error => f.write_str("Error"),
+      Self::Warn => f.write_str("Warn"),
+      Self::Custom(_) => f.write_str("Custom"),
+    }
+  }
+}
+
+/// The package being bundled, which may be imported by its own name
+#[derive(Debug, Clone)]
+struct OwnPackage {
+  name: SmolStr,
+  dir: PathBuf,
+  entry: SmolStr,
+}
+
+/// Modules which are imported in the output instead of being bundled
+#[derive(Debug, Default, Clone)]
+pub struct Externals {
+  /// Packages listed by users or dependencies of the package, subpaths are included
+  packages: Vec<SmolStr>,
+  /// devDependencies are not installed for consumers, so they are not allowed in the output
+  dev_packages: Vec<SmolStr>,
+  /// Packages whose declarations are resolved from `node_modules` and bundled, even if they are dependencies
+  inline_packages: Vec<SmolStr>,
+  own_package: Option<OwnPackage>,
+  unresolved_import: UnresolvedImportPolicy,
+  builtins: bool,
+}
+
+impl Externals {
+  pub fn new(
+    packages: Vec<SmolStr>,
+    inline_packages: Vec<SmolStr>,
+    unresolved_import: UnresolvedImportPolicy,
+  ) -> Self {
+    Self {
+      packages,
+      inline_packages,
+      unresolved_import,
+      ..Default::default()
+    }
+  }
+
+  /// Returns `Ok` if the unresolved `source` imported by `importer` should be treated as external
+  pub fn handle_unresolved_import(&self, importer: &str, source: &str) -> Result<(), Error> {
+    let action = match &self.unresolved_import {
+      UnresolvedImportPolicy::Error => UnresolvedImportAction::Error,
+      UnresolvedImportPolicy::Warn => {
+        log::warn!(
+          "[Externals] unable to resolve \"{}\" imported by {}, it is treated as external",
+          source,
+          importer
+        );
+        UnresolvedImportAction::External
+      }
+      UnresolvedImportPolicy::Custom(callback) => callback(importer, source),
+    };
+
+    match action {
+      UnresolvedImportAction::External => Ok(()),
+      UnresolvedImportAction::Error => Err(Error::new_with_reason(
+        DtsupErrorType::UnresolvedImportError,
+        &format!(
+          "[Externals] unable to resolve \"{}\" imported by {}",
+          source, importer
+        ),
+      )),
+    }
+  }
+
+  /// Treat `dependencies`, `peerDependencies`, `optionalDependencies` of the nearest `package.json`
+  /// and Node.js builtins as externals.
+  pub fn load_package_json(&mut self, dir: &str) -> Result<(), Error> {
+    self.builtins = true;
+
+    let package_json_path = match find_package_json(dir) {
+      Some(path) => path,
+      None => {
+        log::warn!("[Externals] unable to find `package.json` from {}", dir);
+        return Ok(());
+      }
+    };
+
+    log::debug!("[Externals] loading {}", package_json_path.display());
+
+    let package_json: serde_json::Value =
+      serde_json::from_str(&std::fs::read_to_string(&package_json_path)?).map_err(|err| {
+        Error::new_with_reason(
+          DtsupErrorType::PackageJsonError,
+          &format!(
+            "[Externals] failed to parse {}: {}",
+            package_json_path.display(),
+            err
+          ),
+        )
+      })?;
+
+    let get_packages = |fields: &[&str]| {
+      fields
+        .iter()
+        .filter_map(|field| package_json.get(field).and_then(|deps| deps.as_object()))
+        .flat_map(|deps| deps.keys())
+        .map(|name| get_package_name_of_types(name))
+        .collect::<Vec<_>>()
+    };
+
+    self.packages.extend(get_packages(&[
+      "dependencies",
+      "peerDependencies",
+      "optionalDependencies",
+    ]));
+    self.dev_packages.extend(get_packages(&["devDependencies"]));
+
+    Ok(())
+  }
+
+  /// Read the name of the package containing `entry` from the nearest `package.json`,
+  /// so that imports of the package itself are able to be recognized.
+  pub fn load_own_package(&mut self, entry: &str) {
+    let package_json_path = match find_package_json(&nodejs_path::dirname(entry)) {
+      Some(path) => path,
+      None => return,
+    };
+
+    let name = std::fs::read_to_string(&package_json_path)
+      .ok()
+      .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+      .and_then(|package_json| package_json.get("name")?.as_str().map(SmolStr::from));
+
+    if let (Some(name), Some(dir)) = (name, package_json_path.parent()) {
+      log::debug!(
+        "[Externals] found own package `{}` in {}",
+        name,
+        dir.display()
+      );
+
+      self.own_package = Some(OwnPackage {
+        name,
+        dir: dir.to_path_buf(),
+        entry: entry.into(),
+      });
+    }
+  }
+
+  /// Resolve imports of the package itself, e.g. `our-pkg` or `our-pkg/sub` in `our-pkg`,
+  /// to the entry if it is the module exported by the subpath.
+  /// Other subpaths are separate entries, they are imported from the package as externals.
+  pub fn resolve_self_reference(&self, source: &str) -> Option<SmolStr> {
+    let own_package = self.own_package.as_ref()?;
+    let subpath = source.strip_prefix(own_package.name.as_str())?;
+
+    let is_entry = if subpath.is_empty() {
+      true
+    } else {
+      // `our-pkg-foo` is not a subpath of `our-pkg`
+      let subpath = subpath.strip_prefix('/')?;
+      resolve_package_entry(&own_package.dir, subpath)
+        .is_some_and(|resolved| resolved.to_string_lossy() == own_package.entry.as_str())
+    };
+
+    is_entry.then(|| own_package.entry.clone())
+  }
+
+  fn is_self_reference(&self, source: &str) -> bool {
+    self.own_package.as_ref().is_some_and(|own_package| {
+      is_package_or_subpath(source, std::slice::from_ref(&own_package.name))
+    })
+  }
+
+  /// Whether `source` imported by `importer` is external,
+  /// bare imports of inlined packages in `node_modules` are their own dependencies, which are external.
+  pub fn is_external(&self, importer: &str, source: &str) -> bool {
+    if self.is_inline(source) {
+      return false;
+    }
+
+    if self.is_self_reference(source) {
+      return self.resolve_self_reference(source).is_none();
+    }
+
+    self.is_builtin(source)
+      || is_package_or_subpath(source, &self.packages)
+      || is_package_or_subpath(source, &self.dev_packages)
+      || (is_bare_import(source) && importer.split('/').any(|dir| dir == "node_modules"))
+  }
+
+  pub fn is_inline(&self, source: &str) -> bool {
+    is_package_or_subpath(source, &self.inline_packages)
+  }
+
+  /// Sources which are only available in devDependencies
+  pub fn is_dev_dependency(&self, source: &str) -> bool {
+    !self.is_builtin(source)
+      && !is_package_or_subpath(source, &self.packages)
+      && is_package_or_subpath(source, &self.dev_packages)
+  }
+
+  fn is_builtin(&self, source: &str) -> bool {
+    self.builtins && (source.starts_with("node:") || NODE_BUILTINS.contains(&source))
+  }
+}
+
+fn find_package_json(dir: &str) -> Option<PathBuf> {
+  Path::new(dir)
+    .ancestors()
+    .map(|dir| dir.join("package.json"))
+    .find(|path| path.is_file())
+}
+
+/// Bare imports matching a package, or subpaths of it, e.g. `react` and `react/jsx-runtime` for `react`
+#[inline]
+fn is_package_or_subpath(source: &str, packages: &[SmolStr]) -> bool {
+  packages.iter().any(|package| {
+    source
+      .strip_prefix(package.as_str())
+      .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+  })
+}
+
+/// Types of `foo` and `@scope/foo` are provided by `@types/foo` and `@types/scope__foo`
+#[inline]
+fn get_package_name_of_types(name: &str) -> SmolStr {
+  match name.strip_prefix("@types/") {
+    Some(name) => match name.split_once("__") {
+      Some((scope, name)) => format!("@{}/{}", scope, name).into(),
+      None => name.into(),
+    },
+    None => name.into(),
+  }
+}
@@ -0,0 +1,268 @@
+use swc_ecma_ast::{
+  Accessibility, Class, ClassDecl, ClassMember, ClassMethod, ClassProp, Decl, MethodKind,
+  ModuleDecl, ModuleItem, Stmt, TsIndexSignature, TsModuleDecl, TsNamespaceBody,
+};
+use swc_ecma_codegen::{text_writer::WriteJs, Emitter, Node, Result};
+
+/// Emit a declaration statement, swc_ecma_codegen 0.101 ignores modifiers and heritage clauses of classes,
+/// e.g. `abstract`, `override`, `implements` and `static` index signatures, so classes and namespaces containing them are written here.
+pub(super) fn emit_module_item<W: WriteJs>(
+  emitter: &mut Emitter<'_, W>,
+  module_item: &ModuleItem,
+) -> Result {
+  match module_item {
+    ModuleItem::Stmt(Stmt::Decl(decl)) if contains_class(decl) => {
+      emit_decl(emitter, decl)?;
+      emitter.wr.write_line()
+    }
+    ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl))
+      if contains_class(&export_decl.decl) =>
+    {
+      emitter.wr.write_keyword(None, "export")?;
+      emitter.wr.write_space()?;
+      emit_decl(emitter, &export_decl.decl)
+    }
+    _ => emitter.emit_module_item(module_item),
+  }
+}
+
+fn contains_class(decl: &Decl) -> bool {
+  match decl {
+    Decl::Class(_) => true,
+    Decl::TsModule(TsModuleDecl { body, .. }) => {
+      matches!(body, Some(body) if namespace_body_contains_class(body))
+    }
+    _ => false,
+  }
+}
+
+fn namespace_body_contains_class(body: &TsNamespaceBody) -> bool {
+  match body {
+    TsNamespaceBody::TsModuleBlock(block) => {
+      block.body.iter().any(|module_item| match module_item {
+        ModuleItem::Stmt(Stmt::Decl(decl)) => contains_class(decl),
+        ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(export_decl)) => {
+          contains_class(&export_decl.decl)
+        }
+        _ => false,
+      })
+    }
+    TsNamespaceBody::TsNamespaceDecl(namespace_decl) => {
+      namespace_body_contains_class(&namespace_decl.body)
+    }
+  }
+}
+
+fn emit_decl<W: WriteJs>(emitter: &mut Emitter<'_, W>, decl: &Decl) -> Result {
+  match decl {
+    Decl::Class(class_decl) => emit_class_decl(emitter, class_decl),
+    Decl::TsModule(module_decl) => emit_ts_module_decl(emitter, module_decl),
+    _ => decl.emit_with(emitter),
+  }
+}
+
+/// `module` is kept for namespaces to be the same as codegen, e.g. `declare module Foo {}`
+fn emit_ts_module_decl<W: WriteJs>(emitter: &mut Emitter<'_, W>, n: &TsModuleDecl) -> Result {
+  if n.declare {
+    emitter.wr.write_keyword(None, "declare")?;
+    emitter.wr.write_space()?;
+  }
+  emitter.wr.write_keyword(None, "module")?;
+  emitter.wr.write_space()?;
+  n.id.emit_with(emitter)?;
+
+  // `namespace A.B {}`
+  let mut body = n.body.as_ref();
+  while let Some(TsNamespaceBody::TsNamespaceDecl(namespace_decl)) = body {
+    emitter.wr.write_punct(None, ".")?;
+    namespace_decl.id.emit_with(emitter)?;
+    body = Some(&namespace_decl.body);
+  }
+  emitter.wr.write_space()?;
+
+  emitter.wr.write_punct(None, "{")?;
+  emitter.wr.increase_indent()?;
+  emitter.wr.write_line()?;
+  if let Some(TsNamespaceBody::TsModuleBlock(block)) = body {
+    for (index, module_item) in block.body.iter().enumerate() {
+      if index > 0 {
+        emitter.wr.write_line()?;
+      }
+      emit_module_item(emitter, module_item)?;
+    }
+  }
+  emitter.wr.decrease_indent()?;
+  emitter.wr.write_punct(None, "}")
+}
+
+fn emit_class_decl<W: WriteJs>(emitter: &mut Emitter<'_, W>, n: &ClassDecl) -> Result {
+  let Class {
+    is_abstract,
+    type_params,
+    super_class,
+    super_type_params,
+    implements,
+    body,
+    ..
+  } = &n.class;
+
+  if n.declare {
+    emitter.wr.write_keyword(None, "declare")?;
+    emitter.wr.write_space()?;
+  }
+  if *is_abstract {
+    emitter.wr.write_keyword(None, "abstract")?;
+    emitter.wr.write_space()?;
+  }
+  emitter.wr.write_keyword(None, "class")?;
+  emitter.wr.write_space()?;
+  n.ident.emit_with(emitter)?;
+  type_params.emit_with(emitter)?;
+
+  if let Some(super_class) = super_class {
+    emitter.wr.write_space()?;
+    emitter.wr.write_keyword(None, "extends")?;
+    emitter.wr.write_space()?;
+    super_class.emit_with(emitter)?;
+    super_type_params.emit_with(emitter)?;
+  }
+
+  if !implements.is_empty() {
+    emitter.wr.write_space()?;
+    emitter.wr.write_keyword(None, "implements")?;
+    emitter.wr.write_space()?;
+    emit_comma_separated(emitter, implements)?;
+  }
+
+  emitter.wr.write_space()?;
+  emitter.wr.write_punct(None, "{")?;
+  emitter.wr.write_line()?;
+  emitter.wr.increase_indent()?;
+  for member in body {
+    match member {
+      ClassMember::ClassProp(n) => emit_class_prop(emitter, n)?,
+      ClassMember::Method(n) => emit_class_method(emitter, n)?,
+      ClassMember::TsIndexSignature(n) => emit_ts_index_signature(emitter, n)?,
+      _ => member.emit_with(emitter)?,
+    }
+    emitter.wr.write_line()?;
+  }
+  emitter.wr.decrease_indent()?;
+  emitter.wr.write_punct(None, "}")
+}
+
+fn emit_class_prop<W: WriteJs>(emitter: &mut Emitter<'_, W>, n: &ClassProp) -> Result {
+  emit_modifiers(
+    emitter,
+    n.accessibility,
+    &[
+      (n.is_static, "static"),
+      (n.is_abstract, "abstract"),
+      (n.is_override, "override"),
+      (n.readonly, "readonly"),
+    ],
+  )?;
+
+  n.key.emit_with(emitter)?;
+  if n.is_optional {
+    emitter.wr.write_punct(None, "?")?;
+  }
+  if let Some(type_ann) = &n.type_ann {
+    emitter.wr.write_punct(None, ":")?;
+    emitter.wr.write_space()?;
+    type_ann.emit_with(emitter)?;
+  }
+  // `readonly a = 1`
+  if let Some(value) = &n.value {
+    emitter.wr.write_space()?;
+    emitter.wr.write_punct(None, "=")?;
+    emitter.wr.write_space()?;
+    value.emit_with(emitter)?;
+  }
+  emitter.wr.write_semi(None)
+}
+
+fn emit_class_method<W: WriteJs>(emitter: &mut Emitter<'_, W>, n: &ClassMethod) -> Result {
+  emit_modifiers(
+    emitter,
+    n.accessibility,
+    &[
+      (n.is_static, "static"),
+      (n.is_abstract, "abstract"),
+      (n.is_override, "override"),
+      (n.kind == MethodKind::Getter, "get"),
+      (n.kind == MethodKind::Setter, "set"),
+    ],
+  )?;
+
+  n.key.emit_with(emitter)?;
+  if n.is_optional {
+    emitter.wr.write_punct(None, "?")?;
+  }
+  n.function.type_params.emit_with(emitter)?;
+  emitter.wr.write_punct(None, "(")?;
+  emit_comma_separated(emitter, &n.function.params)?;
+  emitter.wr.write_punct(None, ")")?;
+  if let Some(return_type) = &n.function.return_type {
+    emitter.wr.write_punct(None, ":")?;
+    emitter.wr.write_space()?;
+    return_type.emit_with(emitter)?;
+  }
+  emitter.wr.write_semi(None)
+}
+
+fn emit_ts_index_signature<W: WriteJs>(
+  emitter: &mut Emitter<'_, W>,
+  n: &TsIndexSignature,
+) -> Result {
+  emit_modifiers(
+    emitter,
+    None,
+    &[(n.is_static, "static"), (n.readonly, "readonly")],
+  )?;
+
+  emitter.wr.write_punct(None, "[")?;
+  emit_comma_separated(emitter, &n.params)?;
+  emitter.wr.write_punct(None, "]")?;
+  if let Some(type_ann) = &n.type_ann {
+    emitter.wr.write_punct(None, ":")?;
+    emitter.wr.write_space()?;
+    type_ann.emit_with(emitter)?;
+  }
+  emitter.wr.write_semi(None)
+}
+
+fn emit_modifiers<W: WriteJs>(
+  emitter: &mut Emitter<'_, W>,
+  accessibility: Option<Accessibility>,
+  modifiers: &[(bool, &'static str)],
+) -> Result {
+  let accessibility = accessibility.map(|accessibility| match accessibility {
+    Accessibility::Public => "public",
+    Accessibility::Protected => "protected",
+    Accessibility::Private => "private",
+  });
+
+  accessibility
+    .into_iter()
+    .chain(
+      modifiers
+        .iter()
+        .filter_map(|(enabled, modifier)| enabled.then_some(*modifier)),
+    )
+    .try_for_each(|modifier| {
+      emitter.wr.write_keyword(None, modifier)?;
+      emitter.wr.write_space()
+    })
+}
+
+fn emit_comma_separated<W: WriteJs, N: Node>(emitter: &mut Emitter<'_, W>, nodes: &[N]) -> Result {
+  for (index, node) in nodes.iter().enumerate() {
+    if index > 0 {
+      emitter.wr.write_punct(None, ",")?;
+      emitter.wr.write_space()?;
+    }
+    node.emit_with(emitter)?;
+  }
+  Ok(())
+}
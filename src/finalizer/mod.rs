@@ -1,62 +1,699 @@
-use std::collections::HashMap;
-use swc_common::Mark;
+use std::collections::{HashMap, HashSet};
 
-use swc_common::util::take::Take;
-use swc_ecma_visit::{noop_fold_type, Fold};
+use linked_hash_map::LinkedHashMap;
+use swc_atoms::{js_word, JsWord};
+use swc_common::{util::take::Take, Mark, Span, SyntaxContext};
+use swc_ecma_codegen::{text_writer::WriteJs, Emitter};
+use swc_ecma_visit::{noop_fold_type, Fold, Visit, VisitMut, VisitMutWith, VisitWith};
 
-use crate::ast::module::{Exports, LocalName};
-use crate::ast::symbol::MarkExt;
+use crate::ast::module::{Exports, ImportIdent, LocalName, Source};
+use crate::ast::scope::Meaning;
+use crate::ast::symbol::{self, MarkExt, SyntaxContextExt};
+use crate::ast::utils::{export_equals_name, get_declaration_ident, is_valid_module_export_ident};
+use crate::graph::{get_export_mark, ExternalImport, ModuleNamespace};
+
+mod emit;
 
 pub struct Finalizer {
   pub(crate) top_level_exports: HashMap<LocalName, Exports>,
+  /// Meanings declared by included statements, keyed by representative marks
+  pub(crate) declared_meanings: HashMap<Mark, Meaning>,
+  pub(crate) external_imports: Vec<ExternalImport>,
+  pub(crate) external_star_exports: Vec<Source>,
+  pub(crate) module_namespaces: Vec<ModuleNamespace>,
+  /// Deconflicted names of declarations, external bindings and namespaces of bundled modules,
+  /// keyed by representative marks
+  pub(crate) binding_names: HashMap<Mark, JsWord>,
+  /// External bindings referenced by statements, other external bindings are only re-exported
+  pub(crate) referenced_externals: HashSet<Mark>,
+}
+
+/// Collects names of symbols referenced or declared in statements
+#[derive(Default)]
+struct NameCollector {
+  names: HashSet<(JsWord, Mark)>,
+}
+
+impl Visit for NameCollector {
+  fn visit_ident(&mut self, n: &swc_ecma_ast::Ident) {
+    if n.span.ctxt != SyntaxContext::empty() {
+      let mark = symbol::SYMBOL_BOX.lock().find_root(n.span.ctxt.as_mark());
+      self.names.insert((n.sym.clone(), mark));
+    }
+  }
 }
 
 impl Finalizer {
-  pub fn generate_exports(&self) -> swc_ecma_ast::ModuleItem {
+  /// Bind each declaration, external import and module namespace to a name which is not used by other symbols
+  /// in statements, e.g. `interface A$1 {}` if `A` is declared by another bundled module, or
+  /// `import { A as A$1 } from "react"` if `A` is declared in bundled modules.
+  pub fn deconflict_bindings(&mut self, module_items: &[swc_ecma_ast::ModuleItem]) {
+    let external_marks = self
+      .external_imports
+      .iter()
+      .map(|external_import| external_import.mark)
+      .collect::<HashSet<_>>();
+    let namespace_marks = self
+      .module_namespaces
+      .iter()
+      .map(|module_namespace| module_namespace.mark)
+      .collect::<HashSet<_>>();
+
+    // declarations of different modules may share a name, the first declaration keeps its name
+    let mut used_names: HashSet<JsWord> = Default::default();
+    module_items
+      .iter()
+      .filter_map(get_declaration_ident)
+      .filter(|ident| ident.span.ctxt != SyntaxContext::empty())
+      .for_each(|ident| {
+        let mark = symbol::SYMBOL_BOX
+          .lock()
+          .find_root(ident.span.ctxt.as_mark());
+        if self.binding_names.contains_key(&mark) {
+          return;
+        }
+
+        let name = get_unique_name(&ident.sym, &used_names);
+        used_names.insert(name.clone());
+        self.binding_names.insert(mark, name);
+      });
+
+    let mut name_collector = NameCollector::default();
+    module_items.visit_with(&mut name_collector);
+
+    name_collector.names.into_iter().for_each(|(name, mark)| {
+      if external_marks.contains(&mark) {
+        self.referenced_externals.insert(mark);
+      } else if !namespace_marks.contains(&mark) && !self.binding_names.contains_key(&mark) {
+        used_names.insert(name);
+      }
+    });
+
+    // external symbols exported by namespaces or `export =` are not able to be re-exported from their sources
+    let exported_marks = self
+      .module_namespaces
+      .iter()
+      .flat_map(|module_namespace| module_namespace.exports.iter().map(|(_, export)| export))
+      .chain(self.top_level_exports.get(&export_equals_name()))
+      .map(|export| symbol::SYMBOL_BOX.lock().find_root(get_export_mark(export)))
+      .filter(|mark| external_marks.contains(mark))
+      .collect::<Vec<_>>();
+    self.referenced_externals.extend(exported_marks);
+
+    self.external_imports.iter().for_each(|external_import| {
+      if !self.referenced_externals.contains(&external_import.mark) {
+        return;
+      }
+
+      let name = get_unique_name(&external_import.local_name, &used_names);
+      used_names.insert(name.clone());
+      self.binding_names.insert(external_import.mark, name);
+    });
+
+    self.module_namespaces.iter().for_each(|module_namespace| {
+      let name = get_unique_name(&module_namespace.local_name, &used_names);
+      used_names.insert(name.clone());
+      self.binding_names.insert(module_namespace.mark, name);
+    });
+  }
+
+  pub fn rename_references(&self, module_items: &mut [swc_ecma_ast::ModuleItem]) {
+    if self.binding_names.is_empty() {
+      return;
+    }
+
+    let mut renamer = BindingRenamer {
+      binding_names: &self.binding_names,
+    };
+    module_items
+      .iter_mut()
+      .for_each(|module_item| module_item.visit_mut_with(&mut renamer));
+  }
+
+  /// Codegen is not able to emit `namespace Foo.Bar {}`, so it is expanded to
+  /// `namespace Foo { export namespace Bar {} }`, which declares the same namespaces.
+  pub fn expand_qualified_namespaces(&self, module_items: &mut [swc_ecma_ast::ModuleItem]) {
+    module_items
+      .iter_mut()
+      .for_each(|module_item| module_item.visit_mut_with(&mut QualifiedNamespaceExpander));
+  }
+
+  /// Generates namespaces of bundled modules, e.g. `declare namespace foo { export { A, B as C }; }`
+  pub fn generate_namespaces(&self) -> Vec<swc_ecma_ast::ModuleItem> {
     use swc_ecma_ast::{
-      ExportNamedSpecifier, ExportSpecifier, Ident, ModuleDecl, ModuleExportName, ModuleItem,
-      NamedExport,
+      Decl, ExportNamedSpecifier, ExportSpecifier, Ident, ModuleDecl, ModuleItem, NamedExport,
+      Stmt, TsModuleBlock, TsModuleDecl, TsModuleName, TsNamespaceBody,
+    };
+
+    self
+      .module_namespaces
+      .iter()
+      .map(|module_namespace| {
+        let specifiers = module_namespace
+          .exports
+          .iter()
+          .map(|(exported_name, export)| {
+            let orig = self.get_local_name(export);
+            ExportSpecifier::Named(ExportNamedSpecifier {
+              span: Default::default(),
+              exported: (*exported_name != orig)
+                .then(|| Finalizer::module_export_name(exported_name.clone(), Default::default())),
+              orig: Finalizer::module_export_name(orig, Default::default()),
+              is_type_only: false,
+            })
+          })
+          .collect::<Vec<_>>();
+
+        ModuleItem::Stmt(Stmt::Decl(Decl::TsModule(TsModuleDecl {
+          span: Default::default(),
+          declare: true,
+          global: false,
+          id: TsModuleName::Ident(Ident::new(
+            self.binding_names[&module_namespace.mark].clone(),
+            Default::default(),
+          )),
+          body: Some(TsNamespaceBody::TsModuleBlock(TsModuleBlock {
+            span: Default::default(),
+            body: vec![ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(
+              NamedExport {
+                span: Default::default(),
+                specifiers,
+                src: None,
+                type_only: false,
+                asserts: None,
+              },
+            ))],
+          })),
+        })))
+      })
+      .collect()
+  }
+
+  /// Name of the binding referenced by an export in the bundle
+  fn get_local_name(&self, export: &Exports) -> JsWord {
+    let (original_name, mark) = match export {
+      Exports::Name(e) => (&e.original_ident, e.mark),
+      Exports::Namespace(e) => (&e.exported_name, e.mark),
     };
 
-    ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport {
-      span: Default::default(),
-      specifiers: self
-        .top_level_exports
-        .values()
-        .filter_map(|export| {
-          let mut exported_name;
-          let mut original_name;
-          let mut mark;
-          match export {
-            Exports::Name(e) => {
-              exported_name = e.exported_name.clone();
-              original_name = e.original_ident.clone();
-              mark = e.mark;
+    let repr_mark = symbol::SYMBOL_BOX.lock().find_root(mark);
+    self
+      .binding_names
+      .get(&repr_mark)
+      .cloned()
+      .unwrap_or_else(|| original_name.clone())
+  }
+
+  /// Generates imports of external modules, imports from the same source are merged,
+  /// type-only imports are separated since `import type A, { B }` is not allowed.
+  pub fn generate_imports(&self) -> Vec<swc_ecma_ast::ModuleItem> {
+    use swc_ecma_ast::{
+      Ident, ImportDecl, ImportDefaultSpecifier, ImportNamedSpecifier, ImportSpecifier,
+      ImportStarAsSpecifier, ModuleDecl, ModuleItem, Str, TsExternalModuleRef, TsImportEqualsDecl,
+      TsModuleRef,
+    };
+
+    let mut src_to_imports: LinkedHashMap<(Source, bool), Vec<&ExternalImport>> =
+      Default::default();
+    self
+      .external_imports
+      .iter()
+      .filter(|external_import| self.referenced_externals.contains(&external_import.mark))
+      .for_each(|external_import| {
+        src_to_imports
+          .entry((external_import.src.clone(), external_import.is_type_only))
+          .or_insert_with(Default::default)
+          .push(external_import);
+      });
+
+    src_to_imports
+      .into_iter()
+      .flat_map(|((src, type_only), external_imports)| {
+        let mut specifier_groups: Vec<Vec<ImportSpecifier>> = Default::default();
+        let mut default_specifier: Option<ImportSpecifier> = None;
+        let mut named_specifiers: Vec<ImportSpecifier> = Default::default();
+        let mut import_equals: Vec<ModuleItem> = Default::default();
+
+        external_imports.into_iter().for_each(|external_import| {
+          let local = Ident::new(
+            self.binding_names[&external_import.mark].clone(),
+            Default::default(),
+          );
+
+          match &external_import.imported {
+            // `import Foo = require("foo")`
+            ImportIdent::Name(name) if *name == export_equals_name() => {
+              import_equals.push(ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(
+                TsImportEqualsDecl {
+                  span: Default::default(),
+                  declare: false,
+                  is_export: false,
+                  is_type_only: type_only,
+                  id: local,
+                  module_ref: TsModuleRef::TsExternalModuleRef(TsExternalModuleRef {
+                    span: Default::default(),
+                    expr: Str {
+                      value: src.clone(),
+                      ..Str::dummy()
+                    },
+                  }),
+                },
+              )));
+            }
+            ImportIdent::Namespace => {
+              specifier_groups.push(vec![ImportSpecifier::Namespace(ImportStarAsSpecifier {
+                span: Default::default(),
+                local,
+              })]);
             }
-            Exports::Namespace(e) => {
-              // currently not supported
-              return None;
+            ImportIdent::Name(name) if *name == js_word!("default") => {
+              default_specifier = Some(ImportSpecifier::Default(ImportDefaultSpecifier {
+                span: Default::default(),
+                local,
+              }));
             }
-          };
+            ImportIdent::Name(name) => {
+              named_specifiers.push(ImportSpecifier::Named(ImportNamedSpecifier {
+                span: Default::default(),
+                imported: (*name != local.sym)
+                  .then(|| Finalizer::module_export_name(name.clone(), Default::default())),
+                local,
+                is_type_only: false,
+              }));
+            }
+          }
+        });
+
+        if type_only {
+          specifier_groups.extend(default_specifier.map(|s| vec![s]));
+          if !named_specifiers.is_empty() {
+            specifier_groups.push(named_specifiers);
+          }
+        } else {
+          let specifiers = default_specifier
+            .into_iter()
+            .chain(named_specifiers)
+            .collect::<Vec<_>>();
+          if !specifiers.is_empty() {
+            specifier_groups.push(specifiers);
+          }
+        }
+
+        specifier_groups
+          .into_iter()
+          .map(move |specifiers| {
+            ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+              span: Default::default(),
+              specifiers,
+              src: Str {
+                value: src.clone(),
+                ..Str::dummy()
+              },
+              type_only,
+              asserts: None,
+            }))
+          })
+          .chain(import_equals)
+      })
+      .collect()
+  }
+
+  /// Generates `export { ... }` for values and `export type { ... }` for types.
+  /// Symbols exported with `export type` or declared without values are type-only,
+  /// symbols which are not declared in the bundle are treated as values.
+  /// External symbols which are not referenced by statements are re-exported from their sources,
+  /// e.g. `export { FC } from "react"`, along with `export * from "react"`.
+  /// `export = Foo` is kept as is.
+  pub fn generate_exports(&self) -> Vec<swc_ecma_ast::ModuleItem> {
+    use swc_ecma_ast::{
+      ExportAll, ExportNamedSpecifier, ExportNamespaceSpecifier, ExportSpecifier, Expr, Ident,
+      ModuleDecl, ModuleItem, NamedExport, Str, TsExportAssignment,
+    };
 
-          Some(ExportSpecifier::Named(ExportNamedSpecifier {
+    let external_indexes = self
+      .external_imports
+      .iter()
+      .enumerate()
+      .map(|(index, external_import)| (external_import.mark, index))
+      .collect::<HashMap<_, _>>();
+
+    let mut local_specifiers: Vec<(bool, ExportSpecifier)> = Default::default();
+    let mut re_export_specifiers: Vec<(usize, bool, ExportSpecifier)> = Default::default();
+    let mut export_assignment: Option<ModuleItem> = None;
+
+    // exports are sorted by names to make the output stable
+    let mut top_level_exports = self.top_level_exports.iter().collect::<Vec<_>>();
+    top_level_exports.sort_by_key(|(name, _)| *name);
+
+    top_level_exports.into_iter().for_each(|(_, export)| {
+      let (exported_name, mark, is_type_only) = match export {
+        Exports::Name(e) => (e.exported_name.clone(), e.mark, e.is_type_only),
+        Exports::Namespace(e) => (e.exported_name.clone(), e.mark, false),
+      };
+
+      if exported_name == export_equals_name() {
+        export_assignment = Some(ModuleItem::ModuleDecl(ModuleDecl::TsExportAssignment(
+          TsExportAssignment {
+            span: Default::default(),
+            expr: Box::new(Expr::Ident(Ident::new(
+              self.get_local_name(export),
+              Default::default(),
+            ))),
+          },
+        )));
+        return;
+      }
+
+      let repr_mark = symbol::SYMBOL_BOX.lock().find_root(mark);
+      let external_index = external_indexes.get(&repr_mark).copied();
+      let is_type_only = is_type_only
+        || external_index.is_some_and(|index| self.external_imports[index].is_type_only)
+        || matches!(self.declared_meanings.get(&repr_mark), Some(meaning) if !meaning.contains(Meaning::VALUE));
+
+      if let Some(index) = external_index.filter(|_| !self.referenced_externals.contains(&repr_mark)) {
+        let exported = Finalizer::module_export_name(exported_name, Default::default());
+        let specifier = match &self.external_imports[index].imported {
+          ImportIdent::Name(name) => ExportSpecifier::Named(ExportNamedSpecifier {
             span: Default::default(),
-            orig: ModuleExportName::Ident(Ident {
-              sym: original_name.clone(),
-              ..Ident::dummy()
-            }),
-            exported: Some(ModuleExportName::Ident(Ident {
-              sym: exported_name.clone(),
-              ..Ident::dummy()
-            })),
+            orig: Finalizer::module_export_name(name.clone(), Default::default()),
+            exported: Some(exported),
             is_type_only: false,
-          }))
-        })
-        .collect::<Vec<_>>(),
-      src: None,
-      type_only: false,
-      asserts: None,
-    }))
+          }),
+          ImportIdent::Namespace => ExportSpecifier::Namespace(ExportNamespaceSpecifier {
+            span: Default::default(),
+            name: exported,
+          }),
+        };
+
+        re_export_specifiers.push((index, is_type_only, specifier));
+        return;
+      }
+
+      let original_name = self.get_local_name(export);
+
+      local_specifiers.push((
+        is_type_only,
+        ExportSpecifier::Named(ExportNamedSpecifier {
+          span: Default::default(),
+          orig: Finalizer::module_export_name(
+            original_name,
+            Span {
+              ctxt: mark.as_ctxt(),
+              ..Default::default()
+            },
+          ),
+          exported: Some(Finalizer::module_export_name(
+            exported_name,
+            Default::default(),
+          )),
+          is_type_only: false,
+        }),
+      ));
+    });
+
+    let named_export = |specifiers: Vec<ExportSpecifier>, src: Option<&Source>, type_only: bool| {
+      ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport {
+        span: Default::default(),
+        specifiers,
+        src: src.map(|src| Str {
+          value: src.clone(),
+          ..Str::dummy()
+        }),
+        type_only,
+        asserts: None,
+      }))
+    };
+
+    let mut exports = vec![];
+
+    // re-exports from the same source are merged, `export * as ns from "foo"` is not able to be merged
+    re_export_specifiers.sort_by_key(|(index, _, _)| *index);
+    let mut src_to_specifiers: LinkedHashMap<(&Source, bool), Vec<ExportSpecifier>> =
+      Default::default();
+    re_export_specifiers
+      .into_iter()
+      .for_each(|(index, type_only, specifier)| {
+        let src = &self.external_imports[index].src;
+        if matches!(specifier, ExportSpecifier::Namespace(_)) {
+          exports.push(named_export(vec![specifier], Some(src), type_only));
+        } else {
+          src_to_specifiers
+            .entry((src, type_only))
+            .or_insert_with(Default::default)
+            .push(specifier);
+        }
+      });
+    exports.extend(
+      src_to_specifiers
+        .into_iter()
+        .map(|((src, type_only), specifiers)| named_export(specifiers, Some(src), type_only)),
+    );
+
+    exports.extend(self.external_star_exports.iter().map(|src| {
+      ModuleItem::ModuleDecl(ModuleDecl::ExportAll(ExportAll {
+        span: Default::default(),
+        src: Str {
+          value: src.clone(),
+          ..Str::dummy()
+        },
+        asserts: None,
+      }))
+    }));
+
+    let (type_specifiers, value_specifiers): (Vec<_>, Vec<_>) = local_specifiers
+      .into_iter()
+      .partition(|(is_type_only, _)| *is_type_only);
+    let specifiers = |specifiers: Vec<(bool, ExportSpecifier)>| {
+      specifiers.into_iter().map(|(_, s)| s).collect::<Vec<_>>()
+    };
+
+    // `export {}` is kept to make sure the output is a module
+    if !value_specifiers.is_empty() || (type_specifiers.is_empty() && exports.is_empty()) {
+      exports.push(named_export(specifiers(value_specifiers), None, false));
+    }
+    if !type_specifiers.is_empty() {
+      exports.push(named_export(specifiers(type_specifiers), None, true));
+    }
+
+    exports.extend(export_assignment);
+
+    exports
+  }
+}
+
+impl Finalizer {
+  /// Emits a generated statement, `type_only` of imports and exports is ignored by swc_ecma_codegen 0.101,
+  /// so `import type` and `export type` are written here.
+  pub fn emit_module_item<W: WriteJs>(
+    emitter: &mut Emitter<'_, W>,
+    module_item: &swc_ecma_ast::ModuleItem,
+  ) -> std::io::Result<()> {
+    use swc_ecma_ast::{
+      ExportSpecifier, ImportDecl, ImportSpecifier, ModuleDecl, ModuleExportName, ModuleItem,
+      NamedExport,
+    };
+
+    let (keyword, src) = match module_item {
+      ModuleItem::ModuleDecl(ModuleDecl::Import(ImportDecl {
+        type_only: true,
+        src,
+        ..
+      })) => ("import", Some(src)),
+      ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(NamedExport {
+        type_only: true,
+        src,
+        ..
+      })) => ("export", src.as_ref()),
+      _ => return emit::emit_module_item(emitter, module_item),
+    };
+
+    emitter.wr.write_keyword(None, keyword)?;
+    emitter.wr.write_space()?;
+    emitter.wr.write_keyword(None, "type")?;
+    emitter.wr.write_space()?;
+
+    // `import type` never mixes default, namespace and named specifiers
+    let mut named_specifiers: Vec<(ModuleExportName, Option<ModuleExportName>)> = vec![];
+    match module_item {
+      ModuleItem::ModuleDecl(ModuleDecl::Import(import_decl)) => {
+        import_decl
+          .specifiers
+          .iter()
+          .try_for_each(|specifier| match specifier {
+            ImportSpecifier::Default(s) => {
+              emitter.emit_module_export_name(&ModuleExportName::Ident(s.local.clone()))
+            }
+            ImportSpecifier::Namespace(s) => {
+              emitter.wr.write_punct(None, "*")?;
+              emitter.wr.write_space()?;
+              emitter.wr.write_keyword(None, "as")?;
+              emitter.wr.write_space()?;
+              emitter.emit_module_export_name(&ModuleExportName::Ident(s.local.clone()))
+            }
+            ImportSpecifier::Named(s) => {
+              named_specifiers.push(match &s.imported {
+                Some(imported) => (
+                  imported.clone(),
+                  Some(ModuleExportName::Ident(s.local.clone())),
+                ),
+                None => (ModuleExportName::Ident(s.local.clone()), None),
+              });
+              Ok(())
+            }
+          })?;
+      }
+      ModuleItem::ModuleDecl(ModuleDecl::ExportNamed(named_export)) => {
+        named_export.specifiers.iter().for_each(|specifier| {
+          if let ExportSpecifier::Named(s) = specifier {
+            named_specifiers.push((s.orig.clone(), s.exported.clone()));
+          }
+        });
+      }
+      _ => unreachable!(),
+    }
+
+    if !named_specifiers.is_empty() {
+      emitter.wr.write_punct(None, "{")?;
+      emitter.wr.write_space()?;
+      for (index, (name, alias)) in named_specifiers.iter().enumerate() {
+        if index > 0 {
+          emitter.wr.write_punct(None, ",")?;
+          emitter.wr.write_space()?;
+        }
+        emitter.emit_module_export_name(name)?;
+        if let Some(alias) = alias {
+          emitter.wr.write_space()?;
+          emitter.wr.write_keyword(None, "as")?;
+          emitter.wr.write_space()?;
+          emitter.emit_module_export_name(alias)?;
+        }
+      }
+      emitter.wr.write_space()?;
+      emitter.wr.write_punct(None, "}")?;
+    }
+
+    if let Some(src) = src {
+      emitter.wr.write_space()?;
+      emitter.wr.write_keyword(None, "from")?;
+      emitter.wr.write_space()?;
+      emitter.emit_module_export_name(&ModuleExportName::Str(src.clone()))?;
+    }
+
+    emitter.wr.write_semi(None)?;
+    emitter.wr.write_line()
+  }
+
+  /// Names which are not identifiers are exported as string literals, e.g. `export { foo as "foo-bar" }`
+  fn module_export_name(name: JsWord, span: Span) -> swc_ecma_ast::ModuleExportName {
+    use swc_ecma_ast::{Ident, ModuleExportName, Str};
+
+    if is_valid_module_export_ident(&name) {
+      ModuleExportName::Ident(Ident {
+        sym: name,
+        span,
+        ..Ident::dummy()
+      })
+    } else {
+      ModuleExportName::Str(Str {
+        value: name,
+        span,
+        ..Str::dummy()
+      })
+    }
+  }
+
+  /// `export declare class Foo {}` and `export class Foo {}` are both valid in dts files,
+  /// but `declare` is required once `export` is stripped
+  fn declare(decl: swc_ecma_ast::Decl) -> swc_ecma_ast::Decl {
+    use swc_ecma_ast::{ClassDecl, Decl, FnDecl, TsEnumDecl, TsModuleDecl, VarDecl};
+
+    match decl {
+      Decl::Class(c) => Decl::Class(ClassDecl { declare: true, ..c }),
+      Decl::Fn(f) => Decl::Fn(FnDecl { declare: true, ..f }),
+      Decl::Var(v) => Decl::Var(VarDecl { declare: true, ..v }),
+      Decl::TsEnum(e) => Decl::TsEnum(TsEnumDecl { declare: true, ..e }),
+      Decl::TsModule(m) => Decl::TsModule(TsModuleDecl { declare: true, ..m }),
+      Decl::TsInterface(_) | Decl::TsTypeAlias(_) => decl,
+    }
+  }
+}
+
+/// Appends a `$N` suffix to `name` until it is not used, e.g. `A$1`
+fn get_unique_name(name: &JsWord, used_names: &HashSet<JsWord>) -> JsWord {
+  let mut unique_name = name.clone();
+  let mut count = 1;
+  while used_names.contains(&unique_name) {
+    unique_name = format!("{}${}", name, count).into();
+    count += 1;
+  }
+  unique_name
+}
+
+/// Renames declarations, external imports and module namespaces, and references to them,
+/// with the names of their bindings
+struct BindingRenamer<'a> {
+  binding_names: &'a HashMap<Mark, JsWord>,
+}
+
+impl VisitMut for BindingRenamer<'_> {
+  /// `export { Item }` in namespaces keeps its exported name, e.g. `export { Item$1 as Item }`
+  fn visit_mut_export_named_specifier(&mut self, n: &mut swc_ecma_ast::ExportNamedSpecifier) {
+    if n.exported.is_none() {
+      n.exported = Some(n.orig.clone());
+    }
+    n.orig.visit_mut_with(self);
+
+    if let Some(swc_ecma_ast::ModuleExportName::Ident(exported)) = &n.exported {
+      if let swc_ecma_ast::ModuleExportName::Ident(orig) = &n.orig {
+        if exported.sym == orig.sym {
+          n.exported = None;
+        }
+      }
+    }
+  }
+
+  fn visit_mut_ident(&mut self, n: &mut swc_ecma_ast::Ident) {
+    if n.span.ctxt != SyntaxContext::empty() {
+      let mark = symbol::SYMBOL_BOX.lock().find_root(n.span.ctxt.as_mark());
+      if let Some(name) = self.binding_names.get(&mark) {
+        n.sym = name.clone();
+      }
+    }
+  }
+}
+
+struct QualifiedNamespaceExpander;
+
+impl VisitMut for QualifiedNamespaceExpander {
+  fn visit_mut_ts_module_decl(&mut self, n: &mut swc_ecma_ast::TsModuleDecl) {
+    use swc_ecma_ast::{
+      Decl, ExportDecl, ModuleDecl, ModuleItem, TsModuleBlock, TsModuleDecl, TsModuleName,
+      TsNamespaceBody,
+    };
+
+    if !matches!(n.body, Some(TsNamespaceBody::TsNamespaceDecl(_))) {
+      n.visit_mut_children_with(self);
+      return;
+    }
+
+    if let Some(TsNamespaceBody::TsNamespaceDecl(namespace_decl)) = n.body.take() {
+      let span = namespace_decl.span;
+      n.body = Some(TsNamespaceBody::TsModuleBlock(TsModuleBlock {
+        span,
+        body: vec![ModuleItem::ModuleDecl(ModuleDecl::ExportDecl(ExportDecl {
+          span,
+          decl: Decl::TsModule(TsModuleDecl {
+            span,
+            declare: false,
+            global: false,
+            id: TsModuleName::Ident(namespace_decl.id),
+            body: Some(*namespace_decl.body),
+          }),
+        }))],
+      }));
+    }
+
+    n.visit_mut_children_with(self);
   }
 }
 
@@ -67,32 +704,60 @@ impl Fold for Finalizer {
     &mut self,
     module_item: swc_ecma_ast::ModuleItem,
   ) -> swc_ecma_ast::ModuleItem {
-    use swc_ecma_ast::{Decl, DefaultDecl, ModuleDecl, ModuleItem, Stmt};
+    use swc_ecma_ast::{
+      ClassDecl, ClassExpr, Decl, DefaultDecl, FnDecl, FnExpr, ModuleDecl, ModuleItem, Stmt,
+      TsImportEqualsDecl,
+    };
 
-    let result = match module_item {
+    match module_item {
       ModuleItem::ModuleDecl(s) => {
         log::debug!("[Finalizer] folding ModuleDecl {:?}", s);
 
         match s {
-          ModuleDecl::ExportDecl(export_decl) => ModuleItem::Stmt(Stmt::Decl(export_decl.decl)),
-          ModuleDecl::ExportNamed(named) => ModuleItem::dummy(),
+          ModuleDecl::ExportDecl(export_decl) => {
+            ModuleItem::Stmt(Stmt::Decl(Finalizer::declare(export_decl.decl)))
+          }
+          ModuleDecl::ExportNamed(_) => ModuleItem::dummy(),
           ModuleDecl::ExportDefaultDecl(decl) => match decl.decl {
             DefaultDecl::TsInterfaceDecl(ts_interface) => {
               ModuleItem::Stmt(Stmt::Decl(Decl::TsInterface(ts_interface)))
             }
+            DefaultDecl::Class(ClassExpr {
+              ident: Some(ident),
+              class,
+            }) => ModuleItem::Stmt(Stmt::Decl(Decl::Class(ClassDecl {
+              ident,
+              declare: true,
+              class,
+            }))),
+            DefaultDecl::Fn(FnExpr {
+              ident: Some(ident),
+              function,
+            }) => ModuleItem::Stmt(Stmt::Decl(Decl::Fn(FnDecl {
+              ident,
+              declare: true,
+              function,
+            }))),
             _ => ModuleItem::dummy(),
           },
-          ModuleDecl::ExportDefaultExpr(expr) => ModuleItem::dummy(),
-          ModuleDecl::Import(import_decl) => ModuleItem::dummy(),
+          ModuleDecl::ExportDefaultExpr(_) => ModuleItem::dummy(),
+          ModuleDecl::Import(_) => ModuleItem::dummy(),
           ModuleDecl::ExportAll(_) => ModuleItem::dummy(),
+          // `export import Foo = Bar.Foo` is exported with `export { Foo }`
+          ModuleDecl::TsImportEquals(import_equals)
+            if import_equals.module_ref.is_ts_entity_name() =>
+          {
+            ModuleItem::ModuleDecl(ModuleDecl::TsImportEquals(TsImportEqualsDecl {
+              is_export: false,
+              ..import_equals
+            }))
+          }
           ModuleDecl::TsImportEquals(_) => ModuleItem::dummy(),
           ModuleDecl::TsExportAssignment(_) => ModuleItem::dummy(),
           ModuleDecl::TsNamespaceExport(_) => ModuleItem::dummy(),
         }
       }
       ModuleItem::Stmt(_) => module_item,
-    };
-
-    result
+    }
   }
 }
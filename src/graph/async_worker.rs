@@ -10,25 +10,25 @@ use swc_atoms::JsWord;
 use tokio::sync::mpsc::Sender;
 
 use crate::ast::module::{Exports, LocalName, ModuleId};
-use crate::ast::module_analyzer::ModuleExportName;
 use crate::ast::{
   self,
   module_analyzer::{ModuleExport, ModuleImport},
 };
+use crate::externals::Externals;
 use crate::graph::{
   ModuleEdge, ModuleEdgeExportAll, ModuleEdgeExportNamed, ModuleEdgeExportNamespace,
   ModuleEdgeImport,
 };
 use crate::result::Error;
-use crate::utils::resolve_id;
 
 type FromModule = ModuleId;
 type ToModule = ModuleId;
 
 #[derive(Debug)]
 pub enum WorkerMessage {
-  NewModule(ast::module::Module),
+  NewModule(Box<ast::module::Module>),
   NewDependency(FromModule, ToModule, ModuleEdge),
+  Error(Error),
 }
 
 impl Display for WorkerMessage {
@@ -43,6 +43,9 @@ impl Display for WorkerMessage {
           from_id, to_id, edge
         )
       }
+      WorkerMessage::Error(error) => {
+        format!("Error: {}", error)
+      }
     };
     f.write_str(&message)
   }
@@ -54,6 +57,7 @@ pub struct AsyncWorker {
   pub modules_to_work: Arc<RwLock<Vec<SmolStr>>>,
   pub worked_modules: Arc<DashSet<SmolStr>>,
   pub resolved_entries: Arc<DashSet<SmolStr>>,
+  pub externals: Arc<Externals>,
 }
 
 impl AsyncWorker {
@@ -72,8 +76,25 @@ impl AsyncWorker {
     &mut self,
     module: &mut ast::module::Module,
     swc_module: &swc_ecma_ast::Module,
-  ) {
-    let sub_modules = module.pre_analyze_sub_modules(swc_module);
+  ) -> Result<(), Error> {
+    let mut sub_modules = module.pre_analyze_sub_modules(swc_module, &self.externals);
+
+    let mut unresolved_sources = module
+      .src_to_resolved_id
+      .iter()
+      .filter(|(_, resolved_id)| !Path::new(resolved_id.as_str()).is_file())
+      .map(|(src, _)| src.clone())
+      .collect::<Vec<_>>();
+    unresolved_sources.sort();
+
+    for src in unresolved_sources {
+      self.externals.handle_unresolved_import(&module.id, &src)?;
+
+      if let Some(resolved_id) = module.src_to_resolved_id.remove(&src) {
+        sub_modules.remove(&resolved_id);
+      }
+      module.external_sources.insert(src);
+    }
 
     log::debug!(
       "[AsyncWorker] discovered submodules from {}: {:?}",
@@ -83,7 +104,9 @@ impl AsyncWorker {
 
     sub_modules.iter().for_each(|module_id| {
       self.modules_to_work.write().push(module_id.clone());
-    })
+    });
+
+    Ok(())
   }
 
   pub async fn add_import_graph(
@@ -93,7 +116,16 @@ impl AsyncWorker {
   ) {
     let mut import: HashSet<ModuleId> = Default::default();
 
-    for module_import in imports.values() {
+    // a module imported by several statements, e.g. `./foo.cjs` and `./foo.cts`, is sorted by the first one
+    let mut imports = imports.values().collect::<Vec<_>>();
+    imports.sort_by_key(|module_import| module_import.index);
+
+    for module_import in imports {
+      // external modules are not parts of the graph
+      if module.external_sources.contains(&module_import.src) {
+        continue;
+      }
+
       let module_id = module.src_to_resolved_id.get(&module_import.src).unwrap();
       if !import.contains(module_id) {
         import.insert(module_id.clone());
@@ -118,25 +150,17 @@ impl AsyncWorker {
     exports: &Vec<ModuleExport>,
   ) {
     for module_export in exports {
-      let mut src: Option<JsWord> = Default::default();
-      let mut module_export_index: Option<u32>;
-
-      match module_export {
-        ModuleExport::Name(e) => {
-          src = e.src.clone();
-          module_export_index = e.index.clone();
-        }
-        ModuleExport::All(e) => {
-          src = Some(e.src.clone());
-          module_export_index = Some(e.index);
-        }
-        ModuleExport::Namespace(e) => {
-          src = Some(e.src.clone());
-          module_export_index = Some(e.index);
-        }
+      let (mut src, module_export_index): (Option<JsWord>, Option<u32>) = match module_export {
+        ModuleExport::Name(e) => (e.src.clone(), e.index),
+        ModuleExport::All(e) => (Some(e.src.clone()), Some(e.index)),
+        ModuleExport::Namespace(e) => (Some(e.src.clone()), Some(e.index)),
       };
 
       if let Some(src) = src.take() {
+        if module.external_sources.contains(&src) {
+          continue;
+        }
+
         let resolved_id = module.src_to_resolved_id.get(&src).unwrap().clone();
         let index = module_export_index.unwrap();
 
@@ -166,17 +190,34 @@ impl AsyncWorker {
 
     if let Some(resolved_id) = self.fetch_job() {
       log::debug!("[AsyncWorker]: running job {}", resolved_id);
-      let mut swc_module = ast::parse::parse_file(resolved_id.clone()).await.unwrap();
-
       let mut module = module::Module::new(module::ModuleOptions {
         id: resolved_id.clone(),
         is_entry: self.resolved_entries.contains(&resolved_id),
       });
 
-      self.discover_module(&mut module, &swc_module);
+      let analyzed = match ast::parse::parse_file(resolved_id.clone()).await {
+        Ok(mut swc_module) => self
+          .discover_module(&mut module, &swc_module)
+          .and_then(|_| module.analyze(&mut swc_module))
+          .and_then(|mut module_analyzer| {
+            let statement_context = std::mem::take(&mut module_analyzer.statement_context);
+            module.generate_statements_from_ctxt(swc_module, statement_context)?;
+            Ok(module_analyzer)
+          }),
+        Err(error) => Err(error),
+      };
 
-      let module_analyzer = module.analyze(&mut swc_module);
-      module.generate_statements_from_ctxt(swc_module, module_analyzer.statement_context);
+      let module_analyzer = match analyzed {
+        Ok(analyzed) => analyzed,
+        Err(error) => {
+          self
+            .resp_tx
+            .send(WorkerMessage::Error(error))
+            .await
+            .unwrap();
+          return;
+        }
+      };
 
       self
         .add_import_graph(&module, &module_analyzer.imports)
@@ -200,7 +241,7 @@ impl AsyncWorker {
               .exports
               .insert(n.exported_name.clone(), Exports::Namespace(n.clone()));
           }
-          ModuleExport::All(n) => {
+          ModuleExport::All(_) => {
             // `export *`s are linked later
           }
         });
@@ -209,7 +250,7 @@ impl AsyncWorker {
 
       self
         .resp_tx
-        .send(WorkerMessage::NewModule(module))
+        .send(WorkerMessage::NewModule(Box::new(module)))
         .await
         .unwrap();
     }
@@ -1,4 +1,4 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{hash_map::Entry, HashMap, HashSet};
 use std::sync::{
   atomic::{AtomicUsize, Ordering},
   Arc,
@@ -6,36 +6,82 @@ use std::sync::{
 
 use dashmap::DashSet;
 use parking_lot::RwLock;
-use petgraph::{visit::EdgeRef, Direction};
 use rayon::prelude::*;
 use smol_str::SmolStr;
-use tokio::sync::mpsc::{self, error::TryRecvError, Sender};
+use swc_atoms::js_word;
+use swc_common::Mark;
+use tokio::sync::mpsc;
 
 use crate::{
   ast::{
     self,
-    module::{self, Exports, ImportIdent, LocalName, ModuleId},
+    module::{self, Exports, ImportIdent, LocalName, ModuleId, Source},
+    module_analyzer::ModuleExport,
+    scope::Meaning,
     symbol,
+    utils::{export_equals_name, get_namespace_ident_name},
   },
+  externals::{Externals, UnresolvedImportPolicy},
   graph::{
     async_worker::{AsyncWorker, WorkerMessage},
-    ModuleEdge, ModuleGraph, ModuleIndex,
+    ModuleGraph, ModuleIndex,
   },
-  result::Error,
+  result::{DtsupErrorType, Error},
   utils::resolve_id,
 };
 
+/// A binding imported from an external module,
+/// the same symbol imported by multiple modules is merged into a single binding.
+#[derive(Debug, Clone)]
+pub struct ExternalImport {
+  pub src: Source,
+  pub imported: ImportIdent,
+  /// Name of the binding in the first module importing it
+  pub local_name: LocalName,
+  /// Representative mark of the binding
+  pub mark: Mark,
+  /// Type-only if all of the merged imports are type-only
+  pub is_type_only: bool,
+}
+
+/// Namespace object of a bundled module, which is generated as a namespace exporting the module's exports,
+/// e.g. `import * as foo from "./foo"`, `export * as foo from "./foo"` or `typeof import("./foo")`
+#[derive(Debug, Clone)]
+pub struct ModuleNamespace {
+  /// Name of the first binding referencing the namespace
+  pub local_name: LocalName,
+  /// Representative mark of the namespace
+  pub mark: Mark,
+  /// Exports of the module sorted by names
+  pub exports: Vec<(LocalName, Exports)>,
+}
+
 #[derive(Debug)]
 pub struct Graph {
   resolved_entry: ModuleId,
   entry_module_index: ModuleIndex,
   module_graph: ModuleGraph,
   id_to_module: HashMap<ModuleId, ast::module::Module>,
+  externals: Arc<Externals>,
+  auto_externals: bool,
+  /// External imports referenced by included statements or exports, in the order they are imported
+  external_imports: Vec<ExternalImport>,
+  /// External modules star exported by the entry, e.g. `export * from "react"`
+  external_star_exports: Vec<Source>,
+  /// Namespaces of bundled modules, only included namespaces are kept after tree-shaking
+  module_namespaces: Vec<ModuleNamespace>,
 }
 
 #[derive(Debug)]
 pub struct GraphOptions {
   pub entry: SmolStr,
+  /// Modules(and their subpaths) which are imported instead of being bundled
+  pub externals: Vec<SmolStr>,
+  /// Treat dependencies of the nearest `package.json` and Node.js builtins as externals
+  pub auto_externals: bool,
+  /// Packages which are resolved from `node_modules` and bundled
+  pub inline_packages: Vec<SmolStr>,
+  pub unresolved_import: UnresolvedImportPolicy,
 }
 
 impl Graph {
@@ -47,15 +93,33 @@ impl Graph {
       entry_module_index: Default::default(),
       id_to_module: Default::default(),
       module_graph: ModuleGraph::new(),
+      externals: Arc::new(Externals::new(
+        options.externals,
+        options.inline_packages,
+        options.unresolved_import,
+      )),
+      auto_externals: options.auto_externals,
+      external_imports: Default::default(),
+      external_star_exports: Default::default(),
+      module_namespaces: Default::default(),
     }
   }
 
   pub async fn build(&mut self) -> Result<(), Error> {
+    let externals = Arc::make_mut(&mut self.externals);
+    externals.load_own_package(&self.resolved_entry);
+    if self.auto_externals {
+      externals.load_package_json(&nodejs_path::dirname(self.resolved_entry.as_str()))?;
+    }
+
     self.generate().await?;
     self.sort_modules();
     self.link_export_all();
-    self.link_modules();
+    self.link_namespaces();
+    self.link_modules()?;
+    self.link_external_imports();
     self.include_with_tree_shaking();
+    self.validate_external_imports()?;
 
     Ok(())
   }
@@ -80,6 +144,7 @@ impl Graph {
         modules_to_work: modules_to_work.clone(),
         worked_modules: worked_modules.clone(),
         resolved_entries: Arc::new(DashSet::from_iter(vec![self.resolved_entry.clone()])),
+        externals: self.externals.clone(),
       };
 
       tokio::spawn(async move {
@@ -101,16 +166,19 @@ impl Graph {
 
     drop(tx);
 
+    let mut first_error: Option<Error> = None;
+
     while let Some(worker_message) = rx.recv().await {
       use WorkerMessage::*;
       log::debug!("[AsyncWorker] Received new message -> {}", worker_message);
       match worker_message {
         NewModule(module) => {
           let id = module.id.clone();
-          self.id_to_module.insert(id.clone(), module);
-          let module_index = self.module_graph.get_or_add_module(id.clone());
+          let is_entry = module.is_entry;
+          self.id_to_module.insert(id.clone(), *module);
+          let module_index = self.module_graph.get_or_add_module(id);
 
-          if id == self.resolved_entry {
+          if is_entry {
             self.entry_module_index = module_index;
           }
         }
@@ -121,77 +189,214 @@ impl Graph {
             .module_graph
             .add_edge(from_module_index, to_module_index, edge);
         }
+        Error(error) => {
+          log::debug!("[Graph] failed to build module: {}", error);
+          first_error.get_or_insert(error);
+        }
       }
     }
 
+    if let Some(error) = first_error {
+      return Err(error);
+    }
+
     log::debug!("[Graph] generated module graph {:#?}", self.module_graph);
 
     Ok(())
   }
 
+  /// Resolve `export *`s of every module with ES-spec semantics:
+  /// local exports and named re-exports shadow star exports, `default` is never star exported,
+  /// names provided by multiple star exports with different symbols are ambiguous and excluded,
+  /// and cycles are resolved by visiting each module only once.
   fn link_export_all(&mut self) {
     let mut module_visited: HashSet<ModuleIndex> = Default::default();
 
-    self
+    let resolved_exports = self
       .get_sorted_modules()
-      .clone()
       .into_iter()
-      .for_each(|module_index| {
-        if module_visited.contains(&module_index) {
-          return;
-        }
-        module_visited.insert(module_index);
-
-        let source_module_ids = self
-          .module_graph
-          .get_edges_directed(module_index, Direction::Incoming)
-          .map(|edge| {
-            edge.id();
-            return (
-              self.module_graph.get_module_id_by_index(&edge.source()),
-              edge.weight().clone(),
-            );
-          })
-          .collect::<Vec<_>>();
-
-        source_module_ids.into_iter().for_each(|(module_id, edge)| {
-          let module_exports = self
-            .get_module_by_module_index_mut(&module_index)
-            .exports
-            .clone();
-          let target_module_id = self.get_module_by_module_index(&module_index).id.clone();
-          let dep_module = self.id_to_module.get_mut(&module_id).unwrap();
-
-          match edge {
-            ModuleEdge::ExportAll(_) => {
-              module_exports
-                .into_iter()
-                .for_each(|(local_name, module_export)| {
-                  log::debug!(
-                    "[Graph] linking export all with identifier: `{}` from {} to {}",
-                    local_name,
-                    target_module_id,
-                    module_id
-                  );
+      .filter(|module_index| module_visited.insert(*module_index))
+      .map(|module_index| {
+        let exports = self
+          .resolve_exports(module_index, &mut Default::default())
+          .into_iter()
+          .map(|(name, (_, export))| (name, export))
+          .collect::<HashMap<_, _>>();
+        (module_index, exports)
+      })
+      .collect::<Vec<_>>();
 
-                  match dep_module.exports.entry(local_name.clone()) {
-                    std::collections::hash_map::Entry::Vacant(v) => {
-                      v.insert(module_export);
-                    }
-                    std::collections::hash_map::Entry::Occupied(o) => {
-                      // FIXME: we cannot sure if export all identifier is the same with the imports, local name detect is loosy, we should figure it out later.
-                      if !dep_module.imports.contains_key(&local_name) {
-                        // TODO: should we eliminate the panic if local_name is defined at the same statement?
-                        panic!("[Graph] duplicated key detected: {}", local_name);
-                      }
-                    }
-                  }
-                })
+    resolved_exports
+      .into_iter()
+      .for_each(|(module_index, exports)| {
+        self.get_module_by_module_index_mut(&module_index).exports = exports;
+      });
+  }
+
+  /// Exports are paired with the modules declaring the export statements,
+  /// since sources of re-exports are relative to them.
+  fn resolve_exports(
+    &self,
+    module_index: ModuleIndex,
+    export_star_set: &mut HashSet<ModuleIndex>,
+  ) -> HashMap<LocalName, (ModuleIndex, Exports)> {
+    // circular `export *`s
+    if !export_star_set.insert(module_index) {
+      return Default::default();
+    }
+
+    let module = self.get_module_by_module_index(&module_index);
+    let mut exports = module
+      .exports
+      .iter()
+      .map(|(name, export)| (name.clone(), (module_index, export.clone())))
+      .collect::<HashMap<_, _>>();
+    // `None` if the name is ambiguous
+    let mut star_exports: HashMap<LocalName, Option<(ModuleIndex, Exports)>> = Default::default();
+
+    module.local_exports.iter().for_each(|module_export| {
+      let export_all = match module_export {
+        ModuleExport::All(export_all) => export_all,
+        _ => return,
+      };
+
+      if module.external_sources.contains(&export_all.src) {
+        return;
+      }
+
+      let target_module_index = module
+        .src_to_resolved_id
+        .get(&export_all.src)
+        .and_then(|resolved_id| self.module_graph.get_module_index_by_id(resolved_id))
+        .unwrap();
+
+      self
+        .resolve_exports(target_module_index, export_star_set)
+        .into_iter()
+        .for_each(|(name, export)| {
+          if name == js_word!("default") || name == export_equals_name() || exports.contains_key(&name) {
+            return;
+          }
+
+          match star_exports.entry(name.clone()) {
+            Entry::Vacant(v) => {
+              v.insert(Some(export));
+            }
+            Entry::Occupied(mut o) => {
+              // marks are not linked yet, the same symbol may be re-exported by different modules
+              let is_ambiguous = matches!(
+                o.get(),
+                Some((existing_module_index, existing))
+                  if self.resolve_export_origin(*existing_module_index, existing, &mut Default::default())
+                    != self.resolve_export_origin(export.0, &export.1, &mut Default::default())
+              );
+              if is_ambiguous {
+                log::warn!(
+                  "[Graph] `{}` is exported by multiple `export *`s in {}, it is excluded since it is ambiguous",
+                  name,
+                  module.id
+                );
+                o.insert(None);
+              }
             }
-            _ => (),
           }
-        })
+        });
+    });
+
+    exports.extend(
+      star_exports
+        .into_iter()
+        .filter_map(|(name, export)| export.map(|export| (name, export))),
+    );
+
+    exports
+  }
+
+  /// Resolve the module declaring an exported symbol and the name of the symbol in the module,
+  /// re-exports and imports are followed, e.g. `export { X } from "./x"` in different modules share the same origin.
+  /// External symbols are identified by their sources.
+  fn resolve_export_origin(
+    &self,
+    module_index: ModuleIndex,
+    export: &Exports,
+    visited: &mut HashSet<(ModuleIndex, LocalName)>,
+  ) -> (SmolStr, LocalName) {
+    let module = self.get_module_by_module_index(&module_index);
+
+    let (src, imported, local_name) = match export {
+      Exports::Name(e) => match &e.src {
+        Some(src) => (
+          src,
+          ImportIdent::Name(e.original_ident.clone()),
+          &e.original_ident,
+        ),
+        None => match module.imports.get(&e.original_ident) {
+          Some(module_import) if !module_import.is_inline => (
+            &module_import.src,
+            module_import.original_ident.clone(),
+            &e.original_ident,
+          ),
+          _ => return (module.id.clone(), e.original_ident.clone()),
+        },
+      },
+      Exports::Namespace(e) => (&e.src, ImportIdent::Namespace, &e.exported_name),
+    };
+
+    self
+      .resolve_import_origin(module, src, &imported, visited)
+      .unwrap_or_else(|| (module.id.clone(), local_name.clone()))
+  }
+
+  /// Resolve the origin of `imported` from `src` in `module`, `None` if it is not exported by the bundled module
+  fn resolve_import_origin(
+    &self,
+    module: &module::Module,
+    src: &Source,
+    imported: &ImportIdent,
+    visited: &mut HashSet<(ModuleIndex, LocalName)>,
+  ) -> Option<(SmolStr, LocalName)> {
+    let target_module_index = match module
+      .src_to_resolved_id
+      .get(src)
+      .and_then(|resolved_id| self.module_graph.get_module_index_by_id(resolved_id))
+    {
+      Some(target_module_index) => target_module_index,
+      None => {
+        let name = match imported {
+          ImportIdent::Name(name) => name.clone(),
+          ImportIdent::Namespace => "*".into(),
+        };
+        return Some((SmolStr::new(src), name));
+      }
+    };
+    let target_module = self.get_module_by_module_index(&target_module_index);
+
+    let name = match imported {
+      ImportIdent::Name(name) => name,
+      ImportIdent::Namespace => return Some((target_module.id.clone(), "*".into())),
+    };
+
+    // circular re-exports
+    if !visited.insert((target_module_index, name.clone())) {
+      return None;
+    }
+
+    if let Some(export) = target_module.exports.get(name) {
+      return Some(self.resolve_export_origin(target_module_index, export, visited));
+    }
+
+    if *name == js_word!("default") {
+      return None;
+    }
+
+    target_module
+      .local_exports
+      .iter()
+      .filter_map(|module_export| match module_export {
+        ModuleExport::All(export_all) => Some(&export_all.src),
+        _ => None,
       })
+      .find_map(|src| self.resolve_import_origin(target_module, src, imported, visited))
   }
 
   fn sort_modules(&mut self) {
@@ -199,80 +404,173 @@ impl Graph {
     log::debug!("[Graph] sorted modules {:#?}", self.get_sorted_modules());
   }
 
-  fn link_modules(&self) {
+  /// Bind namespace imports and `export * as`s of bundled modules to the namespaces of their target modules,
+  /// the same module referenced by multiple modules shares a single namespace.
+  fn link_namespaces(&mut self) {
+    let mut module_namespaces: Vec<ModuleNamespace> = Default::default();
+    let mut module_to_namespace: HashMap<ModuleIndex, usize> = Default::default();
+
+    self
+      .get_sorted_modules()
+      .into_iter()
+      .for_each(|module_index| {
+        let module = self.get_module_by_module_index(&module_index);
+
+        let imports = module
+          .imports
+          .values()
+          .filter(|module_import| matches!(module_import.original_ident, ImportIdent::Namespace))
+          .map(|module_import| {
+            (
+              module_import.index,
+              &module_import.src,
+              module_import.mark,
+              module_import.binding_name(),
+            )
+          });
+        // exported names may be keywords or string literals, which are not able to be used as bindings
+        let exports = module
+          .local_exports
+          .iter()
+          .filter_map(|module_export| match module_export {
+            ModuleExport::Namespace(e) => {
+              Some((e.index, &e.src, e.mark, get_namespace_ident_name(&e.src)))
+            }
+            _ => None,
+          });
+
+        let mut references = imports.chain(exports).collect::<Vec<_>>();
+        references.sort_by_key(|(index, ..)| *index);
+
+        references
+          .into_iter()
+          .for_each(|(_, src, mark, local_name)| {
+            // external namespaces are linked in `link_external_imports`
+            let target_module_index = match module
+              .src_to_resolved_id
+              .get(src)
+              .and_then(|resolved_id| self.module_graph.get_module_index_by_id(resolved_id))
+            {
+              Some(target_module_index) => target_module_index,
+              None => return,
+            };
+
+            let index = *module_to_namespace
+              .entry(target_module_index)
+              .or_insert_with(|| {
+                let mut exports = self
+                  .get_module_by_module_index(&target_module_index)
+                  .exports
+                  .iter()
+                  .filter(|(name, _)| **name != export_equals_name())
+                  .map(|(name, export)| (name.clone(), export.clone()))
+                  .collect::<Vec<_>>();
+                exports.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+                module_namespaces.push(ModuleNamespace {
+                  local_name,
+                  mark: symbol::new_mark(),
+                  exports,
+                });
+                module_namespaces.len() - 1
+              });
+
+            log::debug!("[Graph] linking namespace of {} to {}", src, module.id);
+            symbol::SYMBOL_BOX
+              .lock()
+              .union(module_namespaces[index].mark, mark);
+          });
+      });
+
+    self.module_namespaces = module_namespaces;
+  }
+
+  fn link_modules(&self) -> Result<(), Error> {
     self
       .module_graph
       .get_sorted_modules()
       .clone()
       .into_iter()
       .rev()
-      .for_each(|source_module_index| {
+      .try_for_each(|source_module_index| {
         let source_module = self.get_module_by_module_index(&source_module_index);
 
-        source_module.imports.values().for_each(|module_import| {
-          let target_module = self
-            .id_to_module
-            .get(
-              source_module
-                .src_to_resolved_id
-                .get(&module_import.src)
-                .unwrap(),
-            )
-            .unwrap();
-
-          match &module_import.original_ident {
-            ImportIdent::Name(original_name) => {
-              if let Some(export) = target_module.exports.get(original_name) {
-                log::debug!(
-                  "[Graph] linking symbol `{}`(imported as `{}`) from {} to {}",
-                  original_name,
-                  module_import.local_name,
-                  target_module.id,
-                  source_module.id
-                );
+        source_module
+          .imports
+          .values()
+          .try_for_each(|module_import| {
+            // linked in `link_external_imports`
+            if source_module.external_sources.contains(&module_import.src) {
+              return Ok(());
+            }
 
-                match export {
-                  Exports::Name(e) => {
-                    symbol::SYMBOL_BOX.lock().union(module_import.mark, e.mark);
-                  }
-                  Exports::Namespace(e) => {
-                    symbol::SYMBOL_BOX.lock().union(module_import.mark, e.mark);
+            let target_module = self
+              .id_to_module
+              .get(
+                source_module
+                  .src_to_resolved_id
+                  .get(&module_import.src)
+                  .unwrap(),
+              )
+              .unwrap();
+
+            match &module_import.original_ident {
+              ImportIdent::Name(original_name) => {
+                if let Some(export) = target_module.exports.get(original_name) {
+                  log::debug!(
+                    "[Graph] linking symbol `{}`(imported as `{}`) from {} to {}",
+                    original_name,
+                    module_import.local_name,
+                    target_module.id,
+                    source_module.id
+                  );
+
+                  match export {
+                    Exports::Name(e) => {
+                      symbol::SYMBOL_BOX.lock().union(module_import.mark, e.mark);
+                    }
+                    Exports::Namespace(e) => {
+                      symbol::SYMBOL_BOX.lock().union(module_import.mark, e.mark);
+                    }
                   }
+                } else if self
+                  .resolve_external_source(
+                    source_module,
+                    &module_import.src,
+                    &module_import.original_ident,
+                  )
+                  .is_none()
+                {
+                  return Err(Error::new_with_reason(
+                    DtsupErrorType::UnresolvedImportError,
+                    &format!(
+                      "[Graph] `{}` is imported by {}, but it is not exported by {}",
+                      original_name, source_module.id, target_module.id
+                    ),
+                  ));
                 }
-              } else {
-                panic!(
-                  "[Graph] linking failed, as symbol `{}` imported from {} is not exist",
-                  original_name, target_module.id
-                );
+                // otherwise it is provided by an external module, which is linked in `link_external_imports`
+              }
+              ImportIdent::Namespace => {
+                // already linked in `link_namespaces`
               }
             }
-            ImportIdent::Namespace => {
-              // TODO: link namespace, we should figure out how we handle newly added helpers in the first place.
-            }
-          }
-        });
+
+            Ok(())
+          })?;
 
         source_module
-          .exports
-          .values()
+          .local_exports
+          .iter()
           .for_each(|dep_export| match dep_export {
-            Exports::Name(dep_export_name) => {
+            ModuleExport::Name(dep_export_name) => {
               // only directly(sources are existed in `src_to_resolved_id` map in current module) exported names with src should be linked
-              if dep_export_name.src.is_some()
-                && source_module
-                  .src_to_resolved_id
-                  .get(dep_export_name.src.as_ref().unwrap())
-                  .is_some()
+              if let Some(resolved_id) = dep_export_name
+                .src
+                .as_ref()
+                .and_then(|src| source_module.src_to_resolved_id.get(src))
               {
-                let target_module = self
-                  .id_to_module
-                  .get(
-                    source_module
-                      .src_to_resolved_id
-                      .get(dep_export_name.src.as_ref().unwrap())
-                      .unwrap(),
-                  )
-                  .unwrap();
+                let target_module = self.id_to_module.get(resolved_id).unwrap();
 
                 if let Some(e) = target_module.exports.get(&dep_export_name.original_ident) {
                   log::debug!(
@@ -298,19 +596,202 @@ impl Graph {
                 }
               }
             }
-            Exports::Namespace(e) => {
-              // `export * as xxx from "xxx"` in current module
-              // TODO: we have to link the identifier to target export namespace. But we should figure out how we handle newly added helpers in the first place.
+            ModuleExport::Namespace(_) => {
+              // already linked in `link_namespaces`
+            }
+            ModuleExport::All(_) => {
+              // already resolved in `link_export_all`
             }
-          })
+          });
+
+        Ok(())
+      })
+  }
+
+  /// Sources of external modules which are star exported by the module,
+  /// directly or through `export *`s of local modules.
+  fn get_external_star_sources(
+    &self,
+    module_index: ModuleIndex,
+    visited: &mut HashSet<ModuleIndex>,
+  ) -> Vec<Source> {
+    if !visited.insert(module_index) {
+      return Default::default();
+    }
+
+    let module = self.get_module_by_module_index(&module_index);
+
+    module
+      .local_exports
+      .iter()
+      .flat_map(|module_export| match module_export {
+        ModuleExport::All(export_all) if module.external_sources.contains(&export_all.src) => {
+          vec![export_all.src.clone()]
+        }
+        ModuleExport::All(export_all) => module
+          .src_to_resolved_id
+          .get(&export_all.src)
+          .and_then(|resolved_id| self.module_graph.get_module_index_by_id(resolved_id))
+          .map(|target_module_index| self.get_external_star_sources(target_module_index, visited))
+          .unwrap_or_default(),
+        _ => Default::default(),
+      })
+      .collect()
+  }
+
+  /// Resolve the external module providing `imported` from `src` in `module`,
+  /// names which are not exported by a local module may be provided by `export *`s of external modules.
+  fn resolve_external_source(
+    &self,
+    module: &module::Module,
+    src: &Source,
+    imported: &ImportIdent,
+  ) -> Option<Source> {
+    if module.external_sources.contains(src) {
+      return Some(src.clone());
+    }
+
+    let name = match imported {
+      ImportIdent::Name(name) if *name != js_word!("default") && *name != export_equals_name() => {
+        name
+      }
+      _ => return None,
+    };
+
+    let target_module_index = module
+      .src_to_resolved_id
+      .get(src)
+      .and_then(|resolved_id| self.module_graph.get_module_index_by_id(resolved_id))?;
+
+    if self
+      .get_module_by_module_index(&target_module_index)
+      .exports
+      .contains_key(name)
+    {
+      return None;
+    }
+
+    let external_sources =
+      self.get_external_star_sources(target_module_index, &mut Default::default());
+    if external_sources.len() > 1 {
+      log::warn!(
+        "[Graph] `{}` imported from {} may be provided by multiple external modules {:?}, {} is used",
+        name,
+        src,
+        external_sources,
+        external_sources[0]
+      );
+    }
+
+    external_sources.into_iter().next()
+  }
+
+  /// Merge bindings of the same symbol imported or re-exported from external modules,
+  /// e.g. `import { A } from "react"` in `a.d.ts` and `import { A as B } from "react"` in `b.d.ts`
+  /// are both referencing `A`, so they will be emitted as a single import.
+  fn link_external_imports(&mut self) {
+    let mut external_imports: Vec<ExternalImport> = Default::default();
+    let mut symbol_to_index: HashMap<(Source, ImportIdent), usize> = Default::default();
+
+    self
+      .get_sorted_modules()
+      .into_iter()
+      .for_each(|module_index| {
+        let module = self.get_module_by_module_index(&module_index);
+
+        let imports = module.imports.values().filter_map(|module_import| {
+          let src = self.resolve_external_source(
+            module,
+            &module_import.src,
+            &module_import.original_ident,
+          )?;
+
+          Some((
+            module_import.index,
+            ExternalImport {
+              src,
+              imported: module_import.original_ident.clone(),
+              local_name: module_import.binding_name(),
+              mark: module_import.mark,
+              is_type_only: module_import.is_type_only,
+            },
+          ))
+        });
+
+        // `export { A } from "react"` or `export * as React from "react"`
+        let re_exports =
+          module
+            .local_exports
+            .iter()
+            .filter_map(|module_export| match module_export {
+              ModuleExport::Name(e) => {
+                let imported = ImportIdent::Name(e.original_ident.clone());
+                let src = self.resolve_external_source(module, e.src.as_ref()?, &imported)?;
+
+                Some((
+                  e.index.unwrap_or_default(),
+                  ExternalImport {
+                    src,
+                    imported,
+                    local_name: e.original_ident.clone(),
+                    mark: e.mark,
+                    is_type_only: e.is_type_only,
+                  },
+                ))
+              }
+              ModuleExport::Namespace(e) if module.external_sources.contains(&e.src) => Some((
+                e.index,
+                ExternalImport {
+                  src: e.src.clone(),
+                  imported: ImportIdent::Namespace,
+                  local_name: get_namespace_ident_name(&e.src),
+                  mark: e.mark,
+                  is_type_only: false,
+                },
+              )),
+              _ => None,
+            });
+
+        // specifiers of the same statement share the index, marks are created in the order of specifiers
+        let mut references = imports.chain(re_exports).collect::<Vec<_>>();
+        references.sort_by_key(|(index, reference)| (*index, reference.mark.as_u32()));
+
+        references.into_iter().for_each(|(_, reference)| {
+          let key = (reference.src.clone(), reference.imported.clone());
+
+          match symbol_to_index.entry(key) {
+            Entry::Occupied(o) => {
+              let external_import = &mut external_imports[*o.get()];
+              symbol::SYMBOL_BOX
+                .lock()
+                .union(external_import.mark, reference.mark);
+              external_import.is_type_only &= reference.is_type_only;
+            }
+            Entry::Vacant(v) => {
+              v.insert(external_imports.len());
+              external_imports.push(reference);
+            }
+          }
+        });
       });
+
+    log::debug!("[Graph] linked external imports {:#?}", external_imports);
+
+    self.external_imports = external_imports;
+
+    let mut external_star_exports =
+      self.get_external_star_sources(self.entry_module_index, &mut Default::default());
+    let mut visited: HashSet<Source> = Default::default();
+    external_star_exports.retain(|src| visited.insert(src.clone()));
+    self.external_star_exports = external_star_exports;
   }
 
   fn include_with_tree_shaking(&mut self) {
     let entry_module = self.get_module_by_module_index(&self.entry_module_index);
 
-    // convert mark to representative mark in disjoint set
-    let mut marks_to_include = HashSet::from_iter(
+    // convert mark to representative mark in disjoint set,
+    // all meanings of exported symbols are included
+    let mut marks_to_include = HashMap::from_iter(
       entry_module
         .exports
         .values()
@@ -321,19 +802,86 @@ impl Graph {
             Exports::Name(e) => e.mark,
             Exports::Namespace(e) => e.mark,
           };
-          symbol::SYMBOL_BOX.lock().find_root(mark)
+          (symbol::SYMBOL_BOX.lock().find_root(mark), Meaning::ALL)
         })
         .collect::<Vec<_>>(),
     );
 
-    self
-      .get_sorted_modules()
+    // modules may reference each other circularly, marks discovered in later modules
+    // may be declared in earlier visited modules, so we include statements until a fixed point is reached
+    loop {
+      let prev_marks_to_include = marks_to_include.clone();
+
+      self
+        .get_sorted_modules()
+        .iter()
+        .rev()
+        .for_each(|module_index| {
+          let module = self.get_module_by_module_index_mut(module_index);
+          module.include_statement_with_mark_set(&mut marks_to_include);
+        });
+
+      // all exports of included namespaces are included
+      self.module_namespaces.iter().for_each(|module_namespace| {
+        let mark = symbol::SYMBOL_BOX.lock().find_root(module_namespace.mark);
+        if !marks_to_include.contains_key(&mark) {
+          return;
+        }
+
+        module_namespace.exports.iter().for_each(|(_, export)| {
+          let mark = symbol::SYMBOL_BOX.lock().find_root(get_export_mark(export));
+          *marks_to_include.entry(mark).or_default() |= Meaning::ALL;
+        });
+      });
+
+      if marks_to_include == prev_marks_to_include {
+        break;
+      }
+    }
+
+    // external imports and namespaces are never declared in modules, their marks are left in `marks_to_include`
+    self.external_imports.retain_mut(|external_import| {
+      external_import.mark = symbol::SYMBOL_BOX.lock().find_root(external_import.mark);
+      marks_to_include.contains_key(&external_import.mark)
+    });
+    self.module_namespaces.retain_mut(|module_namespace| {
+      module_namespace.mark = symbol::SYMBOL_BOX.lock().find_root(module_namespace.mark);
+      marks_to_include.contains_key(&module_namespace.mark)
+    });
+  }
+
+  /// devDependencies are not installed for consumers, types referencing them are broken
+  fn validate_external_imports(&self) -> Result<(), Error> {
+    let dev_dependency = self
+      .external_imports
       .iter()
-      .rev()
-      .for_each(|module_index| {
-        let module = self.get_module_by_module_index_mut(&module_index);
-        module.include_statement_with_mark_set(&mut marks_to_include);
-      })
+      .map(|external_import| &external_import.src)
+      .chain(self.external_star_exports.iter())
+      .find(|src| self.externals.is_dev_dependency(src));
+
+    if let Some(src) = dev_dependency {
+      return Err(Error::new_with_reason(
+        DtsupErrorType::DevDependencyError,
+        &format!(
+          "[Graph] \"{}\" is referenced by the output, but it is only listed in `devDependencies`, move it to `dependencies` or `peerDependencies`",
+          src
+        ),
+      ));
+    }
+
+    Ok(())
+  }
+
+  pub(crate) fn get_external_imports(&self) -> &Vec<ExternalImport> {
+    &self.external_imports
+  }
+
+  pub(crate) fn get_external_star_exports(&self) -> &Vec<Source> {
+    &self.external_star_exports
+  }
+
+  pub(crate) fn get_module_namespaces(&self) -> &Vec<ModuleNamespace> {
+    &self.module_namespaces
   }
 
   pub(crate) fn get_top_level_exports(&self) -> HashMap<LocalName, Exports> {
@@ -363,3 +911,11 @@ impl Graph {
     self.module_graph.get_sorted_modules().clone()
   }
 }
+
+#[inline]
+pub(crate) fn get_export_mark(export: &Exports) -> Mark {
+  match export {
+    Exports::Name(e) => e.mark,
+    Exports::Namespace(e) => e.mark,
+  }
+}
@@ -1,4 +1,5 @@
 mod async_worker;
+#[allow(clippy::module_inception)]
 pub(crate) mod graph;
 pub(crate) mod module_graph;
 
@@ -51,6 +51,12 @@ impl std::fmt::Debug for ModuleGraph {
   }
 }
 
+impl Default for ModuleGraph {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
 impl ModuleGraph {
   pub fn new() -> Self {
     Self {
@@ -73,7 +79,7 @@ impl ModuleGraph {
 
   pub fn get_or_add_module(&mut self, module_id: SmolStr) -> ModuleIndex {
     if let Some(module_index) = self.module_id_to_index.get(&module_id) {
-      module_index.clone()
+      *module_index
     } else {
       self.add_module(module_id)
     }
@@ -93,17 +99,14 @@ impl ModuleGraph {
   }
 
   pub fn get_module_index_by_id(&self, module_id: &ModuleId) -> Option<ModuleIndex> {
-    self
-      .module_id_to_index
-      .get(module_id)
-      .map(|index| index.clone())
+    self.module_id_to_index.get(module_id).copied()
   }
 
   pub fn get_edges_directed(
     &self,
     module_index: ModuleIndex,
     direction: Direction,
-  ) -> Edges<ModuleEdge, Directed> {
+  ) -> Edges<'_, ModuleEdge, Directed> {
     self.inner.edges_directed(module_index, direction)
   }
 
@@ -150,7 +153,7 @@ impl ModuleGraph {
 
       level_edges
         .iter()
-        .for_each(|(module_index, _)| stack.push(module_index.clone()))
+        .for_each(|(module_index, _)| stack.push(*module_index))
     }
 
     self.sorted_modules = sorted;
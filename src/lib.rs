@@ -5,7 +5,9 @@ pub mod graph;
 pub mod result;
 
 pub use dtsup::{Dtsup, DtsupGenerateOptions, DtsupOptions};
+pub use externals::{UnresolvedImportAction, UnresolvedImportCallback, UnresolvedImportPolicy};
 
 mod ast;
+mod externals;
 mod finalizer;
 mod utils;
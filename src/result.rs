@@ -1,10 +1,18 @@
+use std::fmt::{Display, Formatter};
 use std::string::FromUtf8Error;
 use tokio::task::JoinError;
 
 #[derive(Debug)]
+#[allow(clippy::enum_variant_names)]
 pub enum DtsupErrorType {
   GraphMissingError,
   ParseFileError,
+  PackageJsonError,
+  DevDependencyError,
+  UnresolvedImportError,
+  DuplicateDeclarationError,
+  DeclarationError,
+  UnsupportedSyntaxError,
   UTF8Error,
 
   JoinError,
@@ -31,8 +39,29 @@ impl Error {
       reason: Some(reason.to_owned()),
     }
   }
+
+  /// Attach the module where the error occurred to the reason
+  pub(crate) fn with_module(mut self, module_id: &str) -> Self {
+    self.reason = Some(match self.reason {
+      Some(reason) => format!("{} in {}", reason, module_id),
+      None => format!("in {}", module_id),
+    });
+    self
+  }
 }
 
+impl Display for Error {
+  fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+    if let Some(reason) = &self.reason {
+      write!(f, "[{:?}] {}", self.err_type, reason)
+    } else {
+      write!(f, "[{:?}]", self.err_type)
+    }
+  }
+}
+
+impl std::error::Error for Error {}
+
 impl From<JoinError> for Error {
   fn from(_: JoinError) -> Self {
     Error::new(DtsupErrorType::JoinError)
@@ -1,21 +1,178 @@
-use std::fmt::Display;
+use std::path::{Path, PathBuf};
 
 use smol_str::SmolStr;
 
+#[allow(unused_imports)]
 pub use swc_atoms::js_word;
 
+/// Resolve a module id without extension, declarations are preferred over `.ts` and `.tsx` sources,
+/// and directories are resolved to their index files, e.g. `import Foo from "."`.
+/// Sources are imported by their output extensions, e.g. `./foo.js` for `./foo.ts`, `./foo.mjs` for `./foo.mts`
+/// and `./foo.cjs` for `./foo.cts`.
 #[inline]
 pub fn resolve_id(id: &str) -> SmolStr {
-  if id.ends_with(".d.ts") {
+  let output_extensions: [(&str, &[&str]); 4] = [
+    (".js", &[".d.ts", ".ts", ".tsx"]),
+    (".jsx", &[".d.ts", ".tsx"]),
+    (".mjs", &[".d.mts", ".mts"]),
+    (".cjs", &[".d.cts", ".cts"]),
+  ];
+  let output = output_extensions
+    .iter()
+    .find_map(|(output_ext, exts)| Some((id.strip_suffix(output_ext)?, *exts)));
+
+  if let Some((stem, exts)) = output {
+    exts
+      .iter()
+      .map(|ext| format!("{}{}", stem, ext))
+      .find(|candidate| Path::new(candidate).is_file())
+      .unwrap_or_else(|| format!("{}{}", stem, exts[0]))
+      .into()
+  } else if [".ts", ".tsx", ".mts", ".cts"]
+    .iter()
+    .any(|ext| id.ends_with(ext))
+  {
     SmolStr::from(id)
   } else if id.ends_with(".d") {
     let mut str = id.to_owned();
     str.push_str(".ts");
     SmolStr::new(str)
   } else {
-    let mut str = id.to_owned();
-    str.push_str(".d.ts");
-    SmolStr::new(str)
+    [
+      ".d.ts",
+      ".ts",
+      ".tsx",
+      "/index.d.ts",
+      "/index.ts",
+      "/index.tsx",
+    ]
+    .iter()
+    .map(|ext| format!("{}{}", id, ext))
+    .find(|candidate| Path::new(candidate).is_file())
+    .unwrap_or_else(|| format!("{}.d.ts", id))
+    .into()
+  }
+}
+
+/// Sources which are not relative or absolute paths, e.g. `react` or `@scope/foo/bar`
+#[inline]
+pub fn is_bare_import(source: &str) -> bool {
+  !source.starts_with('.') && !source.starts_with('/')
+}
+
+/// Split a bare import into the package name and the subpath, e.g. `@scope/foo` and `bar` for `@scope/foo/bar`
+pub fn split_package_name(source: &str) -> (&str, &str) {
+  let separator = if source.starts_with('@') {
+    source.match_indices('/').nth(1)
+  } else {
+    source.match_indices('/').next()
+  };
+
+  match separator {
+    Some((index, _)) => (&source[..index], &source[index + 1..]),
+    None => (source, ""),
+  }
+}
+
+/// Resolve declarations of a package in `node_modules` from `dir`,
+/// with the `types` condition of `exports`, `types`(or `typings`) field, or `index.d.ts`,
+/// and `@types` packages are looked up if the package does not provide declarations.
+pub fn resolve_package(source: &str, dir: &str) -> Option<SmolStr> {
+  let (package_name, subpath) = split_package_name(source);
+  let types_package_name = match package_name.strip_prefix('@') {
+    Some(scoped) => format!("@types/{}", scoped.replacen('/', "__", 1)),
+    None => format!("@types/{}", package_name),
+  };
+
+  Path::new(dir)
+    .ancestors()
+    .flat_map(|dir| {
+      let node_modules = dir.join("node_modules");
+      [
+        node_modules.join(package_name),
+        node_modules.join(&types_package_name),
+      ]
+    })
+    .filter(|package_dir| package_dir.is_dir())
+    .find_map(|package_dir| resolve_package_entry(&package_dir, subpath))
+    .map(|resolved| SmolStr::new(resolved.to_string_lossy()))
+}
+
+/// Resolve declarations of `subpath` in the package located in `package_dir`, `""` for the main entry
+pub fn resolve_package_entry(package_dir: &Path, subpath: &str) -> Option<PathBuf> {
+  let package_json: Option<serde_json::Value> =
+    std::fs::read_to_string(package_dir.join("package.json"))
+      .ok()
+      .and_then(|content| serde_json::from_str(&content).ok());
+
+  let export_key = if subpath.is_empty() {
+    ".".to_owned()
+  } else {
+    format!("./{}", subpath)
+  };
+
+  let from_package_json = package_json.as_ref().and_then(|package_json| {
+    let exports = package_json.get("exports");
+    let from_exports = exports
+      .and_then(|exports| {
+        // `"exports": { "types": "./index.d.ts" }` is a shorthand of `".": { ... }`
+        let is_subpath_map = exports
+          .as_object()
+          .is_some_and(|exports| exports.keys().any(|key| key.starts_with('.')));
+        if is_subpath_map {
+          exports.get(&export_key)
+        } else if subpath.is_empty() {
+          Some(exports)
+        } else {
+          None
+        }
+      })
+      .and_then(get_types_condition);
+
+    let from_types_field = subpath
+      .is_empty()
+      .then(|| {
+        package_json
+          .get("types")
+          .or_else(|| package_json.get("typings"))
+          .and_then(|types| types.as_str())
+      })
+      .flatten();
+
+    from_exports.or(from_types_field)
+  });
+
+  let candidates = match from_package_json {
+    Some(types) => vec![package_dir.join(types)],
+    None if subpath.is_empty() => vec![package_dir.join("index.d.ts")],
+    None => vec![
+      PathBuf::from(resolve_id(&package_dir.join(subpath).to_string_lossy()).as_str()),
+      package_dir.join(subpath).join("index.d.ts"),
+    ],
+  };
+
+  candidates
+    .into_iter()
+    .map(|candidate| PathBuf::from(nodejs_path::resolve!(&candidate.to_string_lossy())))
+    .find(|candidate| candidate.is_file())
+}
+
+/// Find declarations in a conditional export, e.g. `{ "import": { "types": "./index.d.ts" } }`
+/// or `{ "require": "./index.d.cts" }`
+fn get_types_condition(export: &serde_json::Value) -> Option<&str> {
+  match export {
+    serde_json::Value::String(s)
+      if [".d.ts", ".d.mts", ".d.cts"]
+        .iter()
+        .any(|ext| s.ends_with(ext)) =>
+    {
+      Some(s)
+    }
+    serde_json::Value::Object(conditions) => conditions
+      .get("types")
+      .and_then(|types| types.as_str())
+      .or_else(|| conditions.values().find_map(get_types_condition)),
+    _ => None,
   }
 }
 
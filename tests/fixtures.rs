@@ -0,0 +1,529 @@
+//! Bundles fixtures and compares outputs with snapshots in `tests/snapshots`,
+//! run with `UPDATE_SNAPSHOTS=1` to update snapshots.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use dts_up::{
+  result::Error, Dtsup, DtsupGenerateOptions, DtsupOptions, UnresolvedImportAction,
+  UnresolvedImportPolicy,
+};
+
+fn fixture_dir(fixture: &str) -> PathBuf {
+  Path::new(env!("CARGO_MANIFEST_DIR")).join(fixture)
+}
+
+/// Entries are `index.d.ts`, or `index.ts` for fixtures of sources
+fn options(fixture: &str, externals: &[&str]) -> DtsupOptions<String> {
+  let entry = ["index.d.ts", "index.ts"]
+    .iter()
+    .map(|file_name| fixture_dir(fixture).join(file_name))
+    .find(|entry| entry.is_file())
+    .unwrap_or_else(|| panic!("entry of {} is missing", fixture));
+
+  DtsupOptions {
+    entry: entry.to_string_lossy().into_owned(),
+    externals: externals.iter().map(|s| s.to_string()).collect(),
+    auto_externals: false,
+    inline_packages: vec![],
+    unresolved_import: Default::default(),
+  }
+}
+
+async fn bundle(options: DtsupOptions<String>) -> Result<String, Error> {
+  let mut dtsup = Dtsup::new(options);
+  dtsup.build().await?;
+
+  let result = dtsup.generate(DtsupGenerateOptions::<&str> { outdir: None })?;
+  Ok(String::from_utf8(result.code)?)
+}
+
+async fn assert_snapshot(fixture: &str, externals: &[&str]) {
+  assert_snapshot_with(fixture, options(fixture, externals)).await;
+}
+
+async fn assert_snapshot_with(fixture: &str, options: DtsupOptions<String>) {
+  let output = bundle(options)
+    .await
+    .unwrap_or_else(|error| panic!("failed to bundle {}: {}", fixture, error));
+
+  let name = Path::new(fixture).file_name().unwrap();
+  let snapshot = Path::new(env!("CARGO_MANIFEST_DIR"))
+    .join("tests/snapshots")
+    .join(name)
+    .with_extension("d.ts");
+
+  if std::env::var_os("UPDATE_SNAPSHOTS").is_some() {
+    fs::write(&snapshot, output).unwrap();
+  } else {
+    let expected = fs::read_to_string(&snapshot)
+      .unwrap_or_else(|_| panic!("snapshot {} is missing", snapshot.display()));
+    assert_eq!(output, expected, "output of {} is changed", fixture);
+  }
+}
+
+async fn assert_error(fixture: &str, externals: &[&str], message: &str) {
+  assert_error_with(fixture, options(fixture, externals), message).await;
+}
+
+async fn assert_error_with(fixture: &str, options: DtsupOptions<String>, message: &str) {
+  match bundle(options).await {
+    Ok(output) => panic!("{} is supposed to fail, but got:\n{}", fixture, output),
+    Err(error) => assert!(
+      error.to_string().contains(message),
+      "`{}` does not contain `{}`",
+      error,
+      message
+    ),
+  }
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn namespace_merged_with_variable() {
+  assert_snapshot("tests/fixtures/namespace-merged-with-variable", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn conflicting_declarations() {
+  assert_error(
+    "tests/fixtures/conflicting-declarations",
+    &[],
+    "DuplicateDeclarationError",
+  )
+  .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unsupported_statement() {
+  assert_error(
+    "tests/fixtures/unsupported-statement",
+    &[],
+    "unsupported statement",
+  )
+  .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn extra_semicolon() {
+  assert_snapshot("node-tests/issue-91-extra-semicolon", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn export_star_as() {
+  assert_snapshot("node-tests/export-star-as", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn namespace_keyword_exports() {
+  assert_snapshot("node-tests/namespace-keyword-exports", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn hoist_generic_extends() {
+  assert_snapshot("node-tests/issue-185-hoist-generic-extends", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn import_equals() {
+  assert_snapshot("node-tests/issue-89-import-equals", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn export_as_namespace() {
+  assert_snapshot("node-tests/export-as-namespace", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn global_namespace() {
+  assert_snapshot("node-tests/issue-160-global-namespace", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn inline_import_namespace() {
+  assert_snapshot("node-tests/inline-import-namespace", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn generics() {
+  assert_snapshot("node-tests/generics", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn generic_extends() {
+  assert_snapshot("node-tests/generic-extends", &["react"]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn star_export_shared_re_export() {
+  assert_snapshot("tests/fixtures/star-export-shared-re-export", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn export_star() {
+  assert_snapshot("node-tests/export-star", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn circular_dependency_star() {
+  assert_snapshot("node-tests/circular-dependency-star", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn circular_to_entry() {
+  assert_snapshot("node-tests/circular-to-entry", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn re_export_shadowed_by_local() {
+  assert_snapshot("tests/fixtures/re-export-shadowed-by-local", &["react"]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn export_all_external() {
+  assert_snapshot("node-tests/export-all-external", &["rollup"]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn inline_import_shadowed_by_local() {
+  assert_snapshot("tests/fixtures/inline-import-shadowed-by-local", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn renaming() {
+  assert_snapshot("node-tests/renaming", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn namespace_rename() {
+  assert_snapshot("node-tests/issue-166-namespace-rename", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn type_only_imports() {
+  assert_snapshot("tests/fixtures/type-only-imports", &["react"]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn self_reference() {
+  assert_snapshot("tests/fixtures/self-reference", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn reachable_declaration_error() {
+  assert_error(
+    "tests/fixtures/reachable-declaration-error",
+    &[],
+    "index.ts:2:3 function requires an explicit return type",
+  )
+  .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unreachable_declarations() {
+  assert_snapshot("tests/fixtures/unreachable-declarations", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unused_imports() {
+  assert_snapshot("tests/fixtures/unused-imports", &["fs", "path"]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn output_extensions() {
+  assert_snapshot("tests/fixtures/output-extensions", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn default_expr_name() {
+  assert_snapshot("tests/fixtures/default-expr-name", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn computed_enum_member() {
+  assert_error(
+    "tests/fixtures/computed-enum-member",
+    &[],
+    "index.ts:6:3 enum member `Length` requires a constant initializer",
+  )
+  .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn constant_enum_members() {
+  assert_snapshot("tests/fixtures/constant-enum-members", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn has_class() {
+  assert_snapshot("node-tests/has-class", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn implements_expression() {
+  assert_snapshot("node-tests/implements-expression", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn constructor_shorthands() {
+  assert_snapshot("node-tests/constructor-shorthands", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn getters_setters() {
+  assert_snapshot("node-tests/getters-setters", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn abstract_members() {
+  assert_snapshot("node-tests/ts42-abstract", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn mapped_types() {
+  assert_snapshot("node-tests/type-mapped", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn remapped_mapped_types() {
+  assert_error(
+    "node-tests/remapped-mapped-types",
+    &[],
+    "`as` clauses of mapped types are not supported at 12:20",
+  )
+  .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn call_signature() {
+  assert_snapshot("node-tests/call-signature", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn construct_signature() {
+  assert_snapshot("node-tests/construct-signature", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn getter_setter_signatures() {
+  assert_snapshot("node-tests/ts43-getset", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn static_index_signature() {
+  assert_snapshot("node-tests/ts43-staticindex", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn function_signatures() {
+  assert_snapshot("tests/fixtures/function-signatures", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn overrides() {
+  assert_snapshot("node-tests/overrides", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn export_default_overrides() {
+  assert_snapshot("node-tests/export-default-overrides", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn overrides_with_rename() {
+  assert_snapshot("node-tests/overrides-with-rename", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn declaration_merging() {
+  assert_snapshot("tests/fixtures/declaration-merging", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn separate_meanings() {
+  assert_snapshot("tests/fixtures/separate-meanings", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn export_multiple_vars() {
+  assert_snapshot("node-tests/export-multiple-vars", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn export_const() {
+  assert_snapshot("node-tests/issue-24-export-const", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn conditional_types() {
+  assert_snapshot("node-tests/type-conditional", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn infer_types() {
+  assert_snapshot("tests/fixtures/infer-types", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn type_nodes() {
+  assert_snapshot("node-tests/type-nodes", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn index_signature_types() {
+  assert_snapshot("node-tests/type-index", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn variadic_tuple_types() {
+  assert_snapshot("node-tests/variadic-tuple-types", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn labeled_tuple_types() {
+  assert_snapshot("node-tests/labeled-tuple-types", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn template_literal_types() {
+  assert_snapshot("node-tests/ts43-templatestring", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn function_types() {
+  assert_snapshot("node-tests/type-function", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn constructor_types() {
+  assert_snapshot("node-tests/type-constructor", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn generic_function_types() {
+  assert_snapshot("tests/fixtures/generic-function-types", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn enums() {
+  assert_snapshot("node-tests/enum", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn enum_references() {
+  assert_snapshot("tests/fixtures/enum-references", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn string_literal_names() {
+  assert_snapshot("tests/fixtures/string-literal-names", &[]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn external_imports() {
+  assert_snapshot("tests/fixtures/external-imports", &["react"]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn inline_import_external_namespace() {
+  assert_snapshot("node-tests/inline-import-external-namespace", &["foo"]).await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn auto_externals() {
+  let fixture = "tests/fixtures/auto-externals";
+  assert_snapshot_with(
+    fixture,
+    DtsupOptions {
+      auto_externals: true,
+      ..options(fixture, &[])
+    },
+  )
+  .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn dev_dependency_import() {
+  let fixture = "tests/fixtures/dev-dependency-import";
+  assert_error_with(
+    fixture,
+    DtsupOptions {
+      auto_externals: true,
+      ..options(fixture, &[])
+    },
+    "\"dev-only\" is referenced by the output, but it is only listed in `devDependencies`",
+  )
+  .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn inline_packages() {
+  let fixture = "tests/fixtures/inline-packages";
+  assert_snapshot_with(
+    fixture,
+    DtsupOptions {
+      auto_externals: true,
+      inline_packages: vec!["inlined".into()],
+      ..options(fixture, &[])
+    },
+  )
+  .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unresolved_import_error() {
+  assert_error(
+    "tests/fixtures/unresolved-imports",
+    &[],
+    "unable to resolve \"./missing\" imported by",
+  )
+  .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unresolved_import_warn() {
+  let fixture = "tests/fixtures/unresolved-imports";
+  assert_snapshot_with(
+    fixture,
+    DtsupOptions {
+      unresolved_import: UnresolvedImportPolicy::Warn,
+      ..options(fixture, &[])
+    },
+  )
+  .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn unresolved_import_custom() {
+  let fixture = "tests/fixtures/unresolved-imports";
+  assert_error_with(
+    fixture,
+    DtsupOptions {
+      unresolved_import: UnresolvedImportPolicy::Custom(Arc::new(|importer, source| {
+        assert!(importer.ends_with("unresolved-imports/index.d.ts"));
+        match source {
+          "missing-pkg" => UnresolvedImportAction::Error,
+          _ => UnresolvedImportAction::External,
+        }
+      })),
+      ..options(fixture, &[])
+    },
+    "unable to resolve \"missing-pkg\" imported by",
+  )
+  .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn invalid_utf8() {
+  assert_error(
+    "tests/fixtures/invalid-utf8",
+    &[],
+    "invalid-utf8/index.d.ts is not valid UTF-8",
+  )
+  .await;
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn inferred_literals() {
+  assert_snapshot("tests/fixtures/inferred-literals", &[]).await;
+}
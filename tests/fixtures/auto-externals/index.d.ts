@@ -0,0 +1,15 @@
+import { FC } from "react";
+import { JSX } from "react/jsx-runtime";
+import { Scoped } from "@scope/pkg";
+import { Optional } from "optional";
+import { Stats } from "fs";
+import { ReadStream } from "node:fs";
+
+export interface Props {
+  component: FC;
+  element: JSX.Element;
+  scoped: Scoped;
+  optional: Optional;
+  stats: Stats;
+  stream: ReadStream;
+}
@@ -0,0 +1,7 @@
+export enum Flags {
+  None = 0,
+  Read = 1 << 0,
+  Write = 1 << 1,
+  ReadWrite = Read | Flags.Write,
+  Length = "flags".length,
+}
@@ -0,0 +1,4 @@
+declare const foo: number;
+declare function foo(): void;
+
+export { foo };
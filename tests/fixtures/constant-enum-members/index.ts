@@ -0,0 +1,8 @@
+export enum Flags {
+  None = 0,
+  Read = 1 << 0,
+  Write = 1 << 1,
+  ReadWrite = Read | Flags["Write"],
+  Inverted = ~ReadWrite,
+  Name = `flags`,
+}
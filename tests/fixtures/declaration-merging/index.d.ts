@@ -0,0 +1,38 @@
+interface Options {}
+interface Callback {}
+interface Member {}
+interface Unused {}
+
+declare function fn(options: Options): void;
+declare namespace fn {
+  const callback: Callback;
+}
+
+declare class Klass {}
+declare namespace Klass {
+  interface Member {}
+}
+interface Klass {
+  member: Member;
+}
+
+declare enum Color {
+  Red,
+}
+declare namespace Color {
+  function parse(color: string): Color;
+}
+
+interface Merged {
+  a: Options;
+}
+interface Merged {
+  b: Callback;
+}
+
+declare function unused(): void;
+declare namespace unused {
+  const value: Unused;
+}
+
+export { fn, Klass, Color, Merged };
@@ -0,0 +1,3 @@
+export const _default: string = "named";
+
+export default 42 as number;
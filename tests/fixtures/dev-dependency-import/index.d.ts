@@ -0,0 +1,5 @@
+import { Dev } from "dev-only";
+
+export interface Props {
+  dev: Dev;
+}
@@ -0,0 +1,19 @@
+declare enum Base {
+  A = 1,
+}
+declare const enum Flags {
+  None = 0,
+  A = Base.A,
+}
+declare enum Kind {
+  Circle,
+  Square,
+}
+declare enum Unused {
+  A,
+}
+
+export interface Circle {
+  kind: Kind.Circle;
+  flags: Flags;
+}
@@ -0,0 +1,8 @@
+import { FC, ReactNode, Unused } from "react";
+import * as React from "react";
+
+export interface A {
+  fc: FC;
+  node: ReactNode;
+  ref: React.Ref<A>;
+}
@@ -0,0 +1,10 @@
+import { FC as Component } from "react";
+import R from "react";
+
+interface FC {}
+
+export interface B {
+  component: Component;
+  local: FC;
+  default: R;
+}
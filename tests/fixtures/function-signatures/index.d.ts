@@ -0,0 +1,13 @@
+interface Options {}
+interface Result {}
+interface Rest {}
+interface Destructured {}
+interface Asserted {}
+interface Guarded {}
+interface This {}
+interface Unused {}
+
+export declare function run(this: This, options: Options, ...rest: Rest[]): Result;
+export declare function destructure({ a }: { a: Destructured }): void;
+export declare function assert(value: unknown): asserts value is Asserted;
+export declare function guard(value: unknown): value is Guarded;
@@ -0,0 +1,7 @@
+interface Event {}
+interface E {}
+interface Result {}
+interface Options {}
+
+export type Handler = <E extends Event>(e: E) => Result;
+export type Factory = new <E>(options: Options) => E;
@@ -0,0 +1,7 @@
+interface Box<T> {
+  value: T;
+}
+interface U {}
+interface Fallback {}
+
+export type Unbox<T> = T extends Box<infer U> ? U : Fallback;
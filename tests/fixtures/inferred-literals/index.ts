@@ -0,0 +1,10 @@
+export const str = "foo";
+export const num = -1;
+export const big = 1n;
+export const negativeBig = -1n;
+export const tpl = `bar`;
+export const quoted = `"baz"`;
+
+export let widenedNum = -1;
+export let widenedBig = -1n;
+export let widenedTpl = `bar`;
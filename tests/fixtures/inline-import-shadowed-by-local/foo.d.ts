@@ -0,0 +1,4 @@
+export interface Bar {
+  remote: true;
+}
+export declare const bar: Bar;
@@ -0,0 +1,8 @@
+interface Bar {
+  local: true;
+}
+export interface Foo {
+  remote: import("./foo").Bar;
+  local: Bar;
+  namespace: typeof import("./foo");
+}
@@ -0,0 +1,4 @@
+import { Options, create } from "inlined";
+import type { Sub } from "inlined/sub";
+import type { Cjs } from "inlined/cjs";
+export { Options, create, Sub, Cjs };
@@ -0,0 +1,11 @@
+interface Styled {
+  (tag: string): unknown;
+}
+
+declare const styled: Styled;
+
+declare namespace styled {
+  type Theme = {};
+}
+
+export { styled };
@@ -0,0 +1,19 @@
+import type { A } from "./a.js";
+import type { B } from "./b.jsx";
+import type { C } from "./c.mjs";
+import type { D } from "./d.js";
+import type { E } from "./e.cjs";
+import type { F } from "./f.cjs";
+import type { G } from "./g.mjs";
+import type { E as H } from "./e.cts";
+
+export interface All {
+  a: A;
+  b: B;
+  c: C;
+  d: D;
+  e: E;
+  f: F;
+  g: G;
+  h: H;
+}
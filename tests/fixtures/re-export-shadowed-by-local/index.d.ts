@@ -0,0 +1,6 @@
+interface FC {
+  local: true;
+}
+
+export { FC as ReFC } from "react";
+export declare const fc: FC;
@@ -0,0 +1,9 @@
+class Impl {
+  run(value: string) {
+    return value;
+  }
+}
+
+export function create(): Impl {
+  return new Impl();
+}
@@ -0,0 +1,4 @@
+import { Me } from "my-pkg";
+export interface Helper {
+  me: Me;
+}
@@ -0,0 +1,9 @@
+import type { Me as Alias } from "my-pkg";
+import type { Me as Subpath } from "my-pkg/self";
+import * as Self from "my-pkg";
+export { Helper } from "./helper";
+export interface Me {
+  alias: Alias;
+  subpath: Subpath;
+  namespace: Self.Helper;
+}
@@ -0,0 +1,18 @@
+interface FooConstructor {}
+interface Foo {}
+declare const Foo: FooConstructor;
+
+interface BarType {}
+interface Bar {
+  type: BarType;
+}
+interface BarValue {}
+declare const Bar: BarValue;
+
+export interface UsesTypes {
+  foo: Foo;
+}
+
+export interface UsesValues {
+  bar: typeof Bar;
+}
@@ -0,0 +1,4 @@
+export { X } from "./x";
+export interface Y {
+  a: string;
+}
@@ -0,0 +1,5 @@
+import { X } from "./x";
+export { X };
+export interface Y {
+  b: string;
+}
@@ -0,0 +1 @@
+export interface X {}
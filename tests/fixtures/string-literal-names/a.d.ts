@@ -0,0 +1,4 @@
+interface A {}
+declare const b: A;
+
+export { A as "a-b", b as "c-d" };
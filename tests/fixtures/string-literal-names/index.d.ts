@@ -0,0 +1,4 @@
+import { "a-b" as AB, "c-d" as cd } from "./a";
+
+export { AB as "my-type", cd as "my-value" };
+export { cd };
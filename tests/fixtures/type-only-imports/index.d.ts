@@ -0,0 +1,12 @@
+import type React from "react";
+import type * as ReactNamespace from "react";
+import type { FC, ComponentType as Component } from "react";
+import type { "string-name" as StringName } from "./string-name";
+export interface Props {
+  react: React.ReactNode;
+  namespace: ReactNamespace.ReactElement;
+  fc: FC;
+  component: Component;
+  string: StringName;
+}
+export type { FC as ReactFC } from "react";
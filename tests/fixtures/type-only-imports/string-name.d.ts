@@ -0,0 +1,2 @@
+interface Name {}
+export { Name as "string-name" };
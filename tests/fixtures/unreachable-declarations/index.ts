@@ -0,0 +1,19 @@
+function helper(value) {
+  return value;
+}
+
+const internal = helper(1);
+
+interface Options {
+  size: number;
+}
+
+class Impl {
+  run(): number {
+    return internal;
+  }
+}
+
+export function create(options: Options): Impl {
+  return new Impl();
+}
@@ -0,0 +1,8 @@
+import type { Missing } from "./missing";
+import type { Pkg } from "missing-pkg";
+import type { Found } from "./found";
+export interface Foo {
+  missing: Missing;
+  pkg: Pkg;
+  found: Found;
+}
@@ -0,0 +1,3 @@
+export interface Foo {}
+
+console.log(1);
@@ -0,0 +1,2 @@
+export default interface Helper {}
+export declare function helperName(): void;
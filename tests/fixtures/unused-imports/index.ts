@@ -0,0 +1,13 @@
+import { readFileSync } from "fs";
+import type { Stats } from "fs";
+import * as path from "path";
+import Helper, { helperName } from "./helper";
+import styles from "./styles.css";
+
+export function read(file: string): Stats {
+  helperName();
+  console.log(styles);
+  return readFileSync(path.join(file)) as any;
+}
+
+export declare const helper: Helper;
@@ -0,0 +1,10 @@
+interface I {
+    (arg: string) : string;
+    staticProp: string;
+}
+declare const fn: {
+    (arg: string) : string;
+    staticProp: string;
+};
+export { fn as fn };
+export type { I as I };
@@ -0,0 +1,3 @@
+declare class B {
+}
+export { B as B };
@@ -0,0 +1,4 @@
+interface Foo {
+    new(): any;
+}
+export type { Foo as Foo };
@@ -0,0 +1,33 @@
+interface Options {
+}
+interface Callback {
+}
+interface Member {
+}
+declare function fn(options: Options): void;
+declare module fn {
+    const callback: Callback;
+}
+declare class Klass {
+}
+declare module Klass {
+    interface Member {
+    }
+}
+interface Klass {
+    member: Member;
+}
+declare enum Color {
+    Red
+}
+declare module Color {
+    function parse(color: string): Color;
+}
+interface Merged {
+    a: Options;
+}
+interface Merged {
+    b: Callback;
+}
+export { Color as Color, Klass as Klass, fn as fn };
+export type { Merged as Merged };
@@ -0,0 +1,3 @@
+declare const _default: string;
+declare const _default$1: number;
+export { _default as _default, _default$1 as default };
@@ -0,0 +1,16 @@
+declare enum Base {
+    A = 1
+}
+declare const enum Flags {
+    None = 0,
+    A = Base.A
+}
+declare enum Kind {
+    Circle,
+    Square
+}
+interface Circle {
+    kind: Kind.Circle;
+    flags: Flags;
+}
+export type { Circle as Circle };
@@ -0,0 +1,13 @@
+declare enum A {
+    A = 0
+}
+declare enum B {
+    B = "B"
+}
+declare const enum C {
+    C = 0
+}
+declare const enum D {
+    D = "D"
+}
+export { A as A, B as B, C as C, D as D };
@@ -0,0 +1,17 @@
+declare type In = {
+    a: string;
+};
+declare type Out = {
+    b: number;
+};
+declare const config: {
+    normalize: (inVar: In) => Out;
+};
+declare const options: {
+    normalize: (inVar: In) => Out;
+};
+declare const params: {
+    normalize: (inVar: In) => Out;
+};
+export { config as config, options as options, params as params };
+export type { In as In, Out as Out };
@@ -0,0 +1,6 @@
+interface A {
+}
+declare module foo {
+    export { A };
+}
+export { foo as foo };
@@ -0,0 +1,6 @@
+interface B {
+}
+declare class A {
+}
+export { A as A };
+export type { B as B };
@@ -0,0 +1,15 @@
+import * as React from "react";
+import R, { FC as Component, ReactNode } from "react";
+interface FC {
+}
+interface B {
+    component: Component;
+    local: FC;
+    default: R;
+}
+interface A {
+    fc: Component;
+    node: ReactNode;
+    ref: React.Ref<A>;
+}
+export type { A as A, B as B };
@@ -0,0 +1,21 @@
+interface Options {
+}
+interface Result {
+}
+interface Rest {
+}
+interface Destructured {
+}
+interface Asserted {
+}
+interface Guarded {
+}
+interface This {
+}
+declare function run(this: This, options: Options, ...rest: Rest[]): Result;
+declare function destructure({ a  }: {
+    a: Destructured;
+}): void;
+declare function assert(value: unknown): asserts value is Asserted;
+declare function guard(value: unknown): value is Guarded;
+export { assert as assert, destructure as destructure, guard as guard, run as run };
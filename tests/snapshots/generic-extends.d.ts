@@ -0,0 +1,4 @@
+import { ElementType, ComponentPropsWithRef, ForwardRefExoticComponent } from "react";
+type AnimatedProps<T> = T;
+type AnimatedComponent<T extends ElementType> = ForwardRefExoticComponent<AnimatedProps<ComponentPropsWithRef<T>>>;
+export type { AnimatedComponent as AnimatedComponent, AnimatedProps as AnimatedProps };
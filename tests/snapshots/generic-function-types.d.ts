@@ -0,0 +1,9 @@
+interface Event {
+}
+interface Result {
+}
+interface Options {
+}
+type Handler = <E extends Event>(e: E) => Result;
+type Factory = new <E>(options: Options) => E;
+export type { Factory as Factory, Handler as Handler };
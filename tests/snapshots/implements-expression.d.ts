@@ -0,0 +1,17 @@
+declare module ns {
+    interface Props<T> {
+        foo: T;
+    }
+    class Component<P> {
+        props: P;
+    }
+}
+interface G {
+}
+interface MyComponentProps extends ns.Props<G> {
+    bar: string;
+}
+declare class MyComponent extends ns.Component<MyComponentProps> {
+}
+export { MyComponent as MyComponent };
+export type { MyComponentProps as MyComponentProps };
@@ -0,0 +1,7 @@
+interface Box<T> {
+    value: T;
+}
+interface Fallback {
+}
+type Unbox<T> = T extends Box<infer U> ? U : Fallback;
+export type { Unbox as Unbox };
@@ -0,0 +1,10 @@
+declare const str: "foo";
+declare const num: -1;
+declare const big: 1n;
+declare const negativeBig: -1n;
+declare const tpl: "bar";
+declare const quoted: '"baz"';
+declare let widenedNum: number;
+declare let widenedBig: bigint;
+declare let widenedTpl: string;
+export { big as big, negativeBig as negativeBig, num as num, quoted as quoted, str as str, tpl as tpl, widenedBig as widenedBig, widenedNum as widenedNum, widenedTpl as widenedTpl };
@@ -0,0 +1,6 @@
+import * as foo from "foo";
+interface Foo {
+    ns1: foo;
+    ns2: typeof foo;
+}
+export type { Foo as Foo };
@@ -0,0 +1,11 @@
+interface IBar {
+}
+declare class Bar {
+}
+interface Foo {
+    ns: typeof bar;
+}
+declare module bar {
+    export { Bar, IBar };
+}
+export type { Foo as Foo };
@@ -0,0 +1,16 @@
+interface Bar {
+    remote: true;
+}
+declare const bar: Bar;
+interface Bar$1 {
+    local: true;
+}
+interface Foo {
+    remote: Bar;
+    local: Bar$1;
+    namespace: typeof foo;
+}
+declare module foo {
+    export { Bar, bar };
+}
+export type { Foo as Foo };
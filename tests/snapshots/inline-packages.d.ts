@@ -0,0 +1,9 @@
+import { Dep } from "nested-dep";
+declare type Cjs = number;
+declare type Sub = string;
+interface Options {
+    dep: Dep;
+}
+declare function create(options: Options): void;
+export { create as create };
+export type { Cjs as Cjs, Options as Options, Sub as Sub };
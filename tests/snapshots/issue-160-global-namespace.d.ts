@@ -0,0 +1,21 @@
+interface A {
+}
+interface B {
+}
+declare module global {
+    module Named {
+        export module Core {
+            export { A, B };
+        }
+    }
+    module Foo {
+        export module Bar {
+            export module Baz {
+                export module Quux {
+                    export { A, B };
+                }
+            }
+        }
+    }
+}
+export type { A as A };
@@ -0,0 +1,9 @@
+declare const Item: () => void;
+declare module B {
+    export { Item };
+}
+declare const Item$1: () => void;
+declare module A {
+    export { Item$1 as Item };
+}
+export { A as A, B as B };
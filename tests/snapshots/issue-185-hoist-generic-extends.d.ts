@@ -0,0 +1,9 @@
+declare type Props = Record<string, number>;
+declare class System<T extends Props> {
+    _obj: T;
+    constructor(src: T);
+}
+declare module a {
+    export { Props, System };
+}
+export { a as A };
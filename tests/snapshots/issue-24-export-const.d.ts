@@ -0,0 +1,4 @@
+declare const C = 123;
+declare let L: number;
+declare var V: number;
+export { C as C, L as L, V as V };
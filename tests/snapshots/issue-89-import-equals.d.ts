@@ -0,0 +1,3 @@
+interface Foo {
+}
+export type { Foo as Foo };
@@ -0,0 +1,4 @@
+interface Connection {
+    uri: string;
+}
+export type { Connection as Connection };
@@ -0,0 +1,2 @@
+type Foo = [first: number, second?: string, ...rest: any[]];
+export type { Foo as Foo };
@@ -0,0 +1,5 @@
+declare const _in = "foo";
+declare module foo {
+    export { _in as in };
+}
+export { foo as foo };
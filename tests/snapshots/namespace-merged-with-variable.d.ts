@@ -0,0 +1,9 @@
+interface Styled {
+    (tag: string) : unknown;
+}
+declare const styled: Styled;
+declare module styled {
+    type Theme = {
+    };
+}
+export { styled as styled };
@@ -0,0 +1,25 @@
+interface G {
+}
+interface F {
+}
+interface E {
+}
+interface D {
+}
+interface C {
+}
+interface B {
+}
+interface A {
+}
+interface All {
+    a: A;
+    b: B;
+    c: C;
+    d: D;
+    e: E;
+    f: F;
+    g: G;
+    h: E;
+}
+export type { All as All };
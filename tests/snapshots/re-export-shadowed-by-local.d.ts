@@ -0,0 +1,6 @@
+interface FC {
+    local: true;
+}
+declare const fc: FC;
+export { FC as ReFC } from "react";
+export { fc as fc };
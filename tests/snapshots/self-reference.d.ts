@@ -0,0 +1,12 @@
+interface Helper {
+    me: Me;
+}
+interface Me {
+    alias: Me;
+    subpath: Me;
+    namespace: Self.Helper;
+}
+declare module Self {
+    export { Helper, Me };
+}
+export type { Helper as Helper, Me as Me };
@@ -0,0 +1,12 @@
+interface Foo {
+}
+interface BarValue {
+}
+declare const Bar: BarValue;
+interface UsesTypes {
+    foo: Foo;
+}
+interface UsesValues {
+    bar: typeof Bar;
+}
+export type { UsesTypes as UsesTypes, UsesValues as UsesValues };
@@ -0,0 +1,3 @@
+interface X {
+}
+export type { X as X };
@@ -0,0 +1,5 @@
+interface A {
+}
+declare const b: A;
+export { b as cd, b as "my-value" };
+export type { A as "my-type" };
@@ -0,0 +1,9 @@
+interface GetT {
+}
+interface SetT {
+}
+interface Thing {
+    get size(): GetT;
+    set size(value: GetT | SetT | boolean);
+}
+export type { Thing as Thing };
@@ -0,0 +1,2 @@
+declare function foo<V extends string>(arg: `*${V}*`): V;
+export { foo as foo };
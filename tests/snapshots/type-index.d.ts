@@ -0,0 +1,6 @@
+interface A {
+}
+declare type Foo = {
+    [k: string]: A;
+};
+export type { Foo as Foo };
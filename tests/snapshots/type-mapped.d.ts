@@ -0,0 +1,8 @@
+interface A {
+}
+interface B {
+}
+declare type Foo = {
+    [P in keyof A]: B[P];
+};
+export type { Foo as Foo };
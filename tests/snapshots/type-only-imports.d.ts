@@ -0,0 +1,13 @@
+import type * as ReactNamespace from "react";
+import type React from "react";
+import type { FC, ComponentType as Component } from "react";
+interface Name {
+}
+interface Props {
+    react: React.ReactNode;
+    namespace: ReactNamespace.ReactElement;
+    fc: FC;
+    component: Component;
+    string: Name;
+}
+export type { Props as Props, FC as ReactFC };
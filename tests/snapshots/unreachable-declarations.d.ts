@@ -0,0 +1,8 @@
+interface Options {
+    size: number;
+}
+declare class Impl {
+    run(): number;
+}
+declare function create(options: Options): Impl;
+export { create as create };
@@ -0,0 +1,10 @@
+import type { Missing } from "./missing";
+import type { Pkg } from "missing-pkg";
+interface Found {
+}
+interface Foo {
+    missing: Missing;
+    pkg: Pkg;
+    found: Found;
+}
+export type { Foo as Foo };
@@ -0,0 +1,6 @@
+import type { Stats } from "fs";
+interface Helper {
+}
+declare function read(file: string): Stats;
+declare const helper: Helper;
+export { helper as helper, read as read };